@@ -0,0 +1,55 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use crate::scanner::Finding;
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Baseline {
+    // fingerprint -> entry, so the file stays sorted and diff-friendly
+    findings: BTreeMap<String, BaselineEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BaselineEntry {
+    secret_type: String,
+    file_path: String,
+}
+
+impl Baseline {
+    /// Loads a baseline file; a missing file is an empty baseline.
+    pub fn load(path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    pub fn from_findings(findings: &[Finding]) -> Self {
+        let findings = findings
+            .iter()
+            .map(|f| {
+                (
+                    f.fingerprint(),
+                    BaselineEntry {
+                        secret_type: f.secret_type.clone(),
+                        file_path: f.file_path.clone(),
+                    },
+                )
+            })
+            .collect();
+        Self { findings }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        let json = serde_json::to_string_pretty(self)?;
+        fs::write(path, json + "\n")?;
+        Ok(())
+    }
+
+    pub fn contains(&self, finding: &Finding) -> bool {
+        self.findings.contains_key(&finding.fingerprint())
+    }
+}
@@ -0,0 +1,189 @@
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Io { source: String, error: std::io::Error },
+    Parse { source: String, error: serde_json::Error },
+    InvalidGlob { source: String, glob: String, error: globset::Error },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io { source, error } => write!(f, "cannot read config file {}: {}", source, error),
+            ConfigError::Parse { source, error } => write!(f, "invalid config file {}: {}", source, error),
+            ConfigError::InvalidGlob { source, glob, error } => {
+                write!(f, "invalid glob '{}' in {}: {}", glob, source, error)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+#[derive(Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+struct ConfigFile {
+    #[serde(default)]
+    paths: PathGlobs,
+    #[serde(default)]
+    rules: HashMap<String, PathGlobs>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+struct PathGlobs {
+    #[serde(default)]
+    include: Vec<String>,
+    #[serde(default)]
+    exclude: Vec<String>,
+}
+
+/// Paths (relative to the scanned root, `/`-separated) a scan or a rule applies to:
+/// any `include` glob if there are some, and no `exclude` glob.
+#[derive(Debug, Clone, Default)]
+pub struct PathScope {
+    include: Option<GlobSet>,
+    exclude: Option<GlobSet>,
+}
+
+impl PathScope {
+    pub fn allows(&self, path: &str) -> bool {
+        self.include.as_ref().is_none_or(|globs| globs.is_match(path))
+            && !self.exclude.as_ref().is_some_and(|globs| globs.is_match(path))
+    }
+}
+
+/// Settings read from `--config`.
+#[derive(Debug, Clone, Default)]
+pub struct Config {
+    pub paths: PathScope,
+    /// Rule name -> paths where that rule runs.
+    pub rules: HashMap<String, PathScope>,
+}
+
+impl Config {
+    pub fn load(path: &Path) -> Result<Self, ConfigError> {
+        let source = path.display().to_string();
+        let content = fs::read_to_string(path).map_err(|error| ConfigError::Io {
+            source: source.clone(),
+            error,
+        })?;
+        Self::parse(&source, &content)
+    }
+
+    fn parse(source: &str, content: &str) -> Result<Self, ConfigError> {
+        let file: ConfigFile = serde_json::from_str(content).map_err(|error| ConfigError::Parse {
+            source: source.to_string(),
+            error,
+        })?;
+
+        let mut rules = HashMap::new();
+        for (name, globs) in file.rules {
+            rules.insert(name, compile(source, globs)?);
+        }
+
+        Ok(Self {
+            paths: compile(source, file.paths)?,
+            rules,
+        })
+    }
+}
+
+fn compile(source: &str, globs: PathGlobs) -> Result<PathScope, ConfigError> {
+    Ok(PathScope {
+        include: build_set(source, &globs.include)?,
+        exclude: build_set(source, &globs.exclude)?,
+    })
+}
+
+/// `*` stops at `/`; a glob without `/` matches the file name at any depth,
+/// like in `.gitignore`.
+fn build_set(source: &str, globs: &[String]) -> Result<Option<GlobSet>, ConfigError> {
+    if globs.is_empty() {
+        return Ok(None);
+    }
+
+    let invalid = |glob: &str, error| ConfigError::InvalidGlob {
+        source: source.to_string(),
+        glob: glob.to_string(),
+        error,
+    };
+
+    let mut builder = GlobSetBuilder::new();
+    for glob in globs {
+        let pattern = if glob.contains('/') {
+            glob.trim_start_matches('/').to_string()
+        } else {
+            format!("**/{}", glob)
+        };
+        let compiled = GlobBuilder::new(&pattern)
+            .literal_separator(true)
+            .build()
+            .map_err(|error| invalid(glob, error))?;
+        builder.add(compiled);
+    }
+
+    builder.build().map(Some).map_err(|error| invalid(&globs.join(", "), error))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::patterns::SecretPattern;
+    use crate::scanner::Scanner;
+
+    #[test]
+    fn glob_without_slash_matches_at_any_depth() {
+        let config = Config::parse("test", r#"{"paths": {"exclude": ["*.lock", "docs/*.md"]}}"#).unwrap();
+        assert!(!config.paths.allows("Cargo.lock"));
+        assert!(!config.paths.allows("a/b/yarn.lock"));
+        assert!(!config.paths.allows("docs/guide.md"));
+        // `*` does not cross `/`, and a glob with `/` is anchored at the root.
+        assert!(config.paths.allows("docs/api/guide.md"));
+        assert!(config.paths.allows("src/docs/guide.md"));
+        assert!(config.paths.allows("src/main.rs"));
+    }
+
+    #[test]
+    fn include_globs_limit_scanned_paths() {
+        let config = Config::parse("test", r#"{"paths": {"include": ["src/**"]}}"#).unwrap();
+        assert!(config.paths.allows("src/a/b.rs"));
+        assert!(!config.paths.allows("tests/a.rs"));
+    }
+
+    #[test]
+    fn rule_exclude_applies_only_to_its_rule() {
+        let config = Config::parse("test", r#"{"rules": {"Rule A": {"exclude": ["fixtures/**"]}}}"#).unwrap();
+        let scanner = Scanner::new(vec![
+            SecretPattern::new("Rule A", "secret_a", "").unwrap(),
+            SecretPattern::new("Rule B", "secret_b", "").unwrap(),
+        ])
+        .with_config(config);
+
+        let rules = |path: &str| -> Vec<String> {
+            let mut names: Vec<String> = scanner
+                .scan_content(path, "secret_a secret_b")
+                .into_iter()
+                .map(|f| f.secret_type)
+                .collect();
+            names.sort();
+            names
+        };
+
+        assert!(scanner.in_scope("fixtures/keys.txt"));
+        assert_eq!(rules("fixtures/keys.txt"), ["Rule B"]);
+        assert_eq!(rules("src/keys.txt"), ["Rule A", "Rule B"]);
+    }
+
+    #[test]
+    fn invalid_glob_is_reported() {
+        let error = Config::parse("test", r#"{"paths": {"include": ["src/[a"]}}"#).unwrap_err();
+        assert!(matches!(error, ConfigError::InvalidGlob { .. }));
+    }
+}
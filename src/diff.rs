@@ -0,0 +1,210 @@
+#[derive(Debug, Clone)]
+pub struct FilePatch {
+    pub path: String,
+    pub patch: String,
+}
+
+/// A multi-file diff split per file.
+#[derive(Debug, Default)]
+pub struct ParsedDiff {
+    pub files: Vec<FilePatch>,
+    /// `diff --git` lines of the sections whose new path could not be read.
+    pub unreadable: Vec<String>,
+}
+
+/// Splits a multi-file `git diff` output into one patch per file. The new path
+/// is read from `+++ b/<path>`, quoted or not, so the diff must be produced with
+/// the default `b/` destination prefix. Deleted files are skipped since they add
+/// no lines, as are sections without a `+++` line (binary files, mode changes,
+/// pure renames).
+pub fn parse_diff(diff: &str) -> ParsedDiff {
+    let mut parsed = ParsedDiff::default();
+    let mut current: Option<FilePatch> = None;
+    let mut header: Option<&str> = None;
+
+    for line in diff.lines() {
+        if line.starts_with("diff --git ") {
+            parsed.files.extend(current.take());
+            header = Some(line);
+        } else if let Some(section) = header {
+            if let Some(path) = line.strip_prefix("+++ ") {
+                let path = path.strip_suffix('\t').unwrap_or(path);
+                if path != "/dev/null" {
+                    match new_path(path) {
+                        Some(path) => {
+                            current = Some(FilePatch {
+                                path,
+                                patch: String::new(),
+                            })
+                        }
+                        None => parsed.unreadable.push(section.to_string()),
+                    }
+                }
+            } else if line.starts_with("@@") {
+                header = None;
+            }
+        }
+
+        if header.is_none() && let Some(file) = current.as_mut() {
+            file.patch.push_str(line);
+            file.patch.push('\n');
+        }
+    }
+
+    parsed.files.extend(current);
+    parsed
+}
+
+/// Path of a `+++` header without its `b/` prefix, unquoting C-style quoted names.
+fn new_path(header_path: &str) -> Option<String> {
+    let path = match header_path.strip_prefix('"') {
+        Some(quoted) => unquote(quoted.strip_suffix('"')?)?,
+        None => header_path.to_string(),
+    };
+    path.strip_prefix("b/").filter(|p| !p.is_empty()).map(str::to_string)
+}
+
+/// Reverses git's quoting of unusual file names: backslash escapes and octal bytes.
+fn unquote(quoted: &str) -> Option<String> {
+    let mut bytes = Vec::new();
+    let mut chars = quoted.bytes();
+
+    while let Some(b) = chars.next() {
+        if b != b'\\' {
+            bytes.push(b);
+            continue;
+        }
+        let escaped = match chars.next()? {
+            b'a' => 0x07,
+            b'b' => 0x08,
+            b't' => b'\t',
+            b'n' => b'\n',
+            b'v' => 0x0b,
+            b'f' => 0x0c,
+            b'r' => b'\r',
+            digit @ b'0'..=b'3' => {
+                let mut value = digit - b'0';
+                for _ in 0..2 {
+                    let d = chars.next().filter(|d| matches!(d, b'0'..=b'7'))?;
+                    value = value * 8 + (d - b'0');
+                }
+                value
+            }
+            other => other,
+        };
+        bytes.push(escaped);
+    }
+
+    String::from_utf8(bytes).ok()
+}
+
+/// Returns the lines added by a unified diff hunk list, with their line number in the new file.
+pub fn added_lines(patch: &str) -> Vec<(usize, String)> {
+    let mut added = Vec::new();
+    let mut new_line = 0;
+
+    for line in patch.lines() {
+        if line.starts_with("@@") {
+            new_line = parse_hunk_start(line).unwrap_or(1);
+        } else if let Some(text) = line.strip_prefix('+') {
+            added.push((new_line, text.to_string()));
+            new_line += 1;
+        } else if line.starts_with('-') || line.starts_with('\\') {
+            continue;
+        } else {
+            new_line += 1;
+        }
+    }
+
+    added
+}
+
+fn parse_hunk_start(header: &str) -> Option<usize> {
+    // @@ -old_start,old_len +new_start,new_len @@
+    let new_range = header.split_whitespace().find(|part| part.starts_with('+'))?;
+    let start = new_range[1..].split(',').next()?;
+    start.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn added_lines_are_numbered_in_the_new_file() {
+        let patch = "\
+@@ -1,3 +1,4 @@
+ keep
+-removed
++added one
++added two
+ keep
+@@ -10,2 +11,2 @@ fn context()
+-old
++new
+\\ No newline at end of file
+";
+        assert_eq!(
+            added_lines(patch),
+            vec![
+                (2, "added one".to_string()),
+                (3, "added two".to_string()),
+                (11, "new".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_diff_skips_deleted_files() {
+        let diff = "\
+diff --git a/gone.txt b/gone.txt
+deleted file mode 100644
+--- a/gone.txt
++++ /dev/null
+@@ -1 +0,0 @@
+-bye
+diff --git a/new.txt b/new.txt
+--- /dev/null
++++ b/new.txt
+@@ -0,0 +1 @@
++hello
+";
+        let parsed = parse_diff(diff);
+        let files = parsed.files;
+        assert!(parsed.unreadable.is_empty());
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].path, "new.txt");
+        assert_eq!(added_lines(&files[0].patch), vec![(1, "hello".to_string())]);
+    }
+
+    fn section(new_path: &str) -> String {
+        format!(
+            "diff --git a/x b/x\nindex 0000000..1111111 100644\n--- /dev/null\n+++ {}\n@@ -0,0 +1 @@\n+KEY=1\n",
+            new_path
+        )
+    }
+
+    #[test]
+    fn parse_diff_unquotes_quoted_paths() {
+        let diff = [
+            section("\"b/caf\\303\\251.env\""),
+            section("\"b/tab\\there.env\""),
+            section("b/with space.env\t"),
+        ]
+        .concat();
+        let parsed = parse_diff(&diff);
+        let paths: Vec<&str> = parsed.files.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(paths, ["café.env", "tab\there.env", "with space.env"]);
+        assert!(parsed.unreadable.is_empty());
+    }
+
+    #[test]
+    fn parse_diff_reports_sections_without_the_b_prefix() {
+        // diff.mnemonicPrefix and diff.noprefix headers.
+        let diff = [section("i/conf.env"), section("conf.env"), section("b/ok.env")].concat();
+        let parsed = parse_diff(&diff);
+        assert_eq!(parsed.files.len(), 1);
+        assert_eq!(parsed.files[0].path, "ok.env");
+        assert_eq!(parsed.unreadable.len(), 2);
+    }
+}
@@ -0,0 +1,62 @@
+use serde::Serialize;
+
+use crate::github::GitHubError;
+
+/// Why a file, branch or commit could not be scanned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorKind {
+    Auth,
+    NotFound,
+    RateLimit,
+    Decode,
+    TooLarge,
+    Network,
+    Api,
+    Config,
+    /// A local file or directory that could not be read.
+    Io,
+}
+
+impl ErrorKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ErrorKind::Auth => "auth",
+            ErrorKind::NotFound => "not found",
+            ErrorKind::RateLimit => "rate limit",
+            ErrorKind::Decode => "decode",
+            ErrorKind::TooLarge => "too large",
+            ErrorKind::Network => "network",
+            ErrorKind::Api => "api",
+            ErrorKind::Config => "config",
+            ErrorKind::Io => "io",
+        }
+    }
+}
+
+/// Part of a scan that failed; any of them makes the scan partial.
+#[derive(Debug, Clone, Serialize)]
+pub struct ScanError {
+    /// Set when several repositories are scanned together.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub repo: Option<String>,
+    /// File path, `branch <name>`, `commit <sha>` or `branch list`.
+    pub target: String,
+    pub kind: ErrorKind,
+    pub message: String,
+}
+
+impl ScanError {
+    pub fn new(target: impl Into<String>, kind: ErrorKind, message: impl ToString) -> Self {
+        Self {
+            repo: None,
+            target: target.into(),
+            kind,
+            message: message.to_string(),
+        }
+    }
+
+    pub fn github(target: impl Into<String>, error: &GitHubError) -> Self {
+        Self::new(target, error.kind(), error)
+    }
+}
@@ -0,0 +1,153 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+use std::sync::LazyLock;
+
+/// Substrings that mark a value as an example rather than a real secret.
+/// Matched case-insensitively; extended with `--stopwords`.
+const DEFAULT_STOPWORDS: &[&str] = &[
+    "example", "placeholder", "changeme", "change_me", "change-me", "your_", "your-", "yourapikey",
+    "yourtoken", "yourpassword", "dummy", "sample", "redacted", "_here", "-here", "insert_",
+    "replace_me", "replaceme", "notasecret", "not_a_secret", "fixme", "todo_",
+];
+
+/// Common words and passwords that are too weak or generic to be a leaked
+/// credential, compared to the value once digits and punctuation are removed.
+const DICTIONARY_WORDS: &[&str] = &[
+    "password", "passwd", "pass", "secret", "mysecret", "supersecret", "mypassword", "admin",
+    "administrator", "root", "letmein", "welcome", "qwerty", "azerty", "default", "test", "testing",
+    "guest", "user", "username", "login", "token", "apikey", "changeit", "master", "demo", "local",
+    "localhost", "development", "production", "staging", "foo", "bar", "baz", "foobar", "abc",
+    "abcdef", "hello", "world", "helloworld", "monkey", "dragon", "iloveyou", "sunshine",
+    "football", "baseball", "trustno", "none", "null", "undefined", "empty", "value", "string",
+];
+
+static QUOTED: LazyLock<Regex> = LazyLock::new(|| Regex::new(r#""([^"]+)"|'([^']+)'"#).unwrap());
+static ASSIGNED: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"[:=]\s*([^\s'\x22]+)\s*$").unwrap());
+static URL_PASSWORD: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"://[^/\s:@]+:([^/\s:@]+)@").unwrap());
+static INTERPOLATION: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\$\{[^}]*\}|\{\{[^}]*\}\}|#\{[^}]*\}|%\([^)]*\)s|^\$[A-Za-z_][A-Za-z0-9_]*$|^%[A-Za-z_][A-Za-z0-9_]*%$|^<[^>]+>$")
+        .unwrap()
+});
+
+/// Why a match was recognized as a placeholder rather than a secret.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FilterReason {
+    /// `${VAR}`, `{{ var }}`, `$VAR`, `<your-key>`...
+    Interpolation,
+    /// Contains a stopword such as `example` or `your_`.
+    Stopword,
+    /// `xxxxxxxx`, `00000000`, or one character over half of the value.
+    RepeatedCharacters,
+    /// A common word or password such as `password123`.
+    DictionaryWord,
+}
+
+impl FilterReason {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            FilterReason::Interpolation => "template interpolation",
+            FilterReason::Stopword => "placeholder stopword",
+            FilterReason::RepeatedCharacters => "repeated characters",
+            FilterReason::DictionaryWord => "dictionary word",
+        }
+    }
+}
+
+/// False-positive stage applied to every match after the rules have run.
+#[derive(Debug, Clone)]
+pub struct PlaceholderFilter {
+    stopwords: Vec<String>,
+    dictionary: HashSet<&'static str>,
+}
+
+impl Default for PlaceholderFilter {
+    fn default() -> Self {
+        Self {
+            stopwords: DEFAULT_STOPWORDS.iter().map(|w| w.to_string()).collect(),
+            dictionary: DICTIONARY_WORDS.iter().copied().collect(),
+        }
+    }
+}
+
+impl PlaceholderFilter {
+    /// Adds the stopwords of a file, one per line; blank lines and `#` comments are ignored.
+    pub fn with_stopwords_file(mut self, path: &Path) -> std::io::Result<Self> {
+        let content = fs::read_to_string(path)?;
+        self.stopwords.extend(
+            content
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                .map(str::to_lowercase),
+        );
+        Ok(self)
+    }
+
+    /// Returns why `matched_text` looks like a placeholder, or `None` if it may be a real secret.
+    pub fn check(&self, matched_text: &str) -> Option<FilterReason> {
+        let value = secret_value(matched_text);
+        let lower = value.to_lowercase();
+
+        if INTERPOLATION.is_match(value) {
+            return Some(FilterReason::Interpolation);
+        }
+        if self.stopwords.iter().any(|word| lower.contains(word.as_str())) {
+            return Some(FilterReason::Stopword);
+        }
+        if is_repeated(value) {
+            return Some(FilterReason::RepeatedCharacters);
+        }
+
+        let letters: String = lower.chars().filter(|c| c.is_ascii_alphabetic()).collect();
+        if self.dictionary.contains(letters.as_str()) {
+            return Some(FilterReason::DictionaryWord);
+        }
+
+        None
+    }
+}
+
+/// The part of a match that holds the secret: the last quoted string, the
+/// value after `=`/`:`, the password of a URL, or the whole match.
+fn secret_value(matched_text: &str) -> &str {
+    if let Some(caps) = QUOTED.captures_iter(matched_text).last()
+        && let Some(value) = caps.get(1).or_else(|| caps.get(2))
+    {
+        return value.as_str();
+    }
+    if let Some(caps) = URL_PASSWORD.captures(matched_text) {
+        return caps.get(1).map_or(matched_text, |m| m.as_str());
+    }
+    if let Some(caps) = ASSIGNED.captures(matched_text) {
+        return caps.get(1).map_or(matched_text, |m| m.as_str());
+    }
+    matched_text
+}
+
+/// At most two distinct characters, or one character repeated over half of
+/// the value (`sk_live_xxxxxxxxxxxxxxxx`).
+fn is_repeated(value: &str) -> bool {
+    let chars: Vec<char> = value.chars().filter(|c| c.is_ascii_alphanumeric()).collect();
+    if chars.len() < 4 {
+        return false;
+    }
+
+    let distinct: HashSet<char> = chars.iter().map(|c| c.to_ascii_lowercase()).collect();
+    if distinct.len() <= 2 {
+        return true;
+    }
+
+    let threshold = (chars.len() / 2).max(6);
+    let mut run = 1;
+    for pair in chars.windows(2) {
+        run = if pair[0].eq_ignore_ascii_case(&pair[1]) { run + 1 } else { 1 };
+        if run >= threshold {
+            return true;
+        }
+    }
+    false
+}
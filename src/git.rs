@@ -0,0 +1,69 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+const HOOK_MARKER: &str = "# Installed by mini-guardian";
+
+pub fn staged_diff(repo: &Path) -> Result<String, Box<dyn std::error::Error>> {
+    // Pin the prefixes and raw UTF-8 paths whatever the user's config, so that
+    // `diff::parse_diff` can read every file header.
+    run_git(
+        repo,
+        &[
+            "-c",
+            "core.quotePath=false",
+            "diff",
+            "--cached",
+            "--no-color",
+            "--no-ext-diff",
+            "--diff-filter=ACMR",
+            "--src-prefix=a/",
+            "--dst-prefix=b/",
+        ],
+    )
+}
+
+pub fn install_hook(repo: &Path, force: bool) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let hooks_dir = run_git(repo, &["rev-parse", "--git-path", "hooks"])?;
+    let hooks_dir = repo.join(hooks_dir.trim());
+    fs::create_dir_all(&hooks_dir)?;
+
+    let hook_path = hooks_dir.join("pre-commit");
+    if hook_path.exists() && !force {
+        let existing = fs::read_to_string(&hook_path).unwrap_or_default();
+        if !existing.contains(HOOK_MARKER) {
+            return Err(format!(
+                "{} already exists and was not installed by mini-guardian (use --force to overwrite)",
+                hook_path.display()
+            )
+            .into());
+        }
+    }
+
+    let exe = std::env::current_exe()?;
+    let script = format!(
+        "#!/bin/sh\n{}: block commits that add secrets.\nexec \"{}\" scan-staged\n",
+        HOOK_MARKER,
+        exe.display()
+    );
+    fs::write(&hook_path, script)?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&hook_path, fs::Permissions::from_mode(0o755))?;
+    }
+
+    Ok(hook_path)
+}
+
+fn run_git(repo: &Path, args: &[&str]) -> Result<String, Box<dyn std::error::Error>> {
+    let output = Command::new("git").arg("-C").arg(repo).args(args).output()?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("git {} failed: {}", args.join(" "), stderr.trim()).into());
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
@@ -1,64 +1,600 @@
-use octocrab::Octocrab;
+use jsonwebtoken::EncodingKey;
+use octocrab::auth::AppAuth;
+use octocrab::models::{AppId, InstallationId};
+use octocrab::{AuthState, Octocrab, OctocrabBuilder};
+use octocrab::service::middleware::auth_header::AuthHeaderLayer;
+use octocrab::service::middleware::base_uri::BaseUriLayer;
+use octocrab::service::middleware::extra_headers::ExtraHeadersLayer;
+use octocrab::service::middleware::retry::RetryConfig;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use base64::Engine;
 use base64::engine::general_purpose::STANDARD;
+use http::header::{ACCEPT, USER_AGENT};
+use http::{HeaderMap, HeaderValue, StatusCode, Uri};
+use http_body_util::BodyExt;
+use hyper_rustls::HttpsConnectorBuilder;
+use hyper_util::client::legacy::Client;
+use hyper_util::rt::TokioExecutor;
+use rustls::pki_types::CertificateDer;
+use rustls::pki_types::pem::PemObject;
+use rustls::{ClientConfig, RootCertStore};
+use std::fmt;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::diff::FilePatch;
+use crate::error::ErrorKind;
+use crate::paths::{in_skipped_dir, is_scannable_file, should_skip_dir};
+use crate::scanner::CommitInfo;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RepoInfo {
     pub name: String,
     pub full_name: String,
     pub private: bool,
+    /// `public`, `private` or `internal` (GitHub Enterprise).
+    pub visibility: String,
     pub default_branch: String,
+    pub archived: bool,
+    pub fork: bool,
+    pub topics: Vec<String>,
+    /// ISO 8601 timestamp of the last push, if the repository was ever pushed to.
+    pub pushed_at: Option<String>,
 }
 
 #[derive(Debug, Clone)]
 pub struct BranchInfo {
     pub name: String,
+    pub commit_sha: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct RepoFile {
+    pub path: String,
+    pub sha: String,
+    /// Size in bytes, as listed in the tree.
+    pub size: u64,
+}
+
+#[derive(Deserialize)]
+struct TreeResponse {
+    tree: Vec<TreeEntry>,
+    #[serde(default)]
+    truncated: bool,
+}
+
+#[derive(Deserialize)]
+struct TreeEntry {
+    path: String,
+    r#type: String,
+    sha: String,
+    /// Only set for blobs.
+    #[serde(default)]
+    size: u64,
+}
+
+#[derive(Deserialize)]
+struct BlobResponse {
+    #[serde(default)]
+    content: String,
+    #[serde(default)]
+    encoding: String,
+    #[serde(default)]
+    size: u64,
+}
+
+#[derive(Deserialize)]
+struct ApiRepo {
+    name: String,
+    full_name: Option<String>,
+    #[serde(default)]
+    private: bool,
+    visibility: Option<String>,
+    default_branch: Option<String>,
+    #[serde(default)]
+    archived: bool,
+    #[serde(default)]
+    fork: bool,
+    #[serde(default)]
+    topics: Vec<String>,
+    pushed_at: Option<String>,
+}
+
+/// `/installation/repositories` wraps its page in an object.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ApiRepoPage {
+    List(Vec<ApiRepo>),
+    Installation { repositories: Vec<ApiRepo> },
+}
+
+#[derive(Deserialize)]
+struct ApiInstallation {
+    id: u64,
+    account: Option<ApiAccount>,
+}
+
+#[derive(Deserialize)]
+struct ApiAccount {
+    login: String,
+}
+
+#[derive(Deserialize)]
+struct ApiBranch {
+    name: String,
+    commit: ApiCommitRef,
+}
+
+#[derive(Deserialize)]
+struct ApiCommitRef {
+    sha: String,
+}
+
+#[derive(Deserialize)]
+struct ApiCommit {
+    sha: String,
+    commit: ApiCommitDetails,
+    #[serde(default)]
+    files: Vec<ApiCommitFile>,
+}
+
+#[derive(Deserialize)]
+struct ApiCommitDetails {
+    author: Option<ApiCommitAuthor>,
+}
+
+#[derive(Deserialize)]
+struct ApiCommitAuthor {
+    name: String,
+    date: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ApiCommitFile {
+    filename: String,
+    /// Blob SHA of the file after the commit; unset for some removed files.
+    sha: Option<String>,
+    #[serde(default)]
+    status: String,
+    /// Left out for binary files and diffs too large to inline.
+    patch: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ApiErrorBody {
+    message: Option<String>,
+    #[serde(default)]
+    errors: Vec<ApiErrorDetail>,
+}
+
+#[derive(Deserialize)]
+struct ApiErrorDetail {
+    code: Option<String>,
+}
+
+/// A file changed by a commit whose diff GitHub did not include.
+#[derive(Debug, Clone)]
+pub struct UnpatchedFile {
+    pub path: String,
+    /// The file's blob at the commit.
+    pub sha: String,
+}
+
+pub enum BlobContent {
+    Text(String),
+    /// Over the caller's size limit, not fetched.
+    TooLarge { size: u64 },
+}
+
+#[derive(Debug, Clone)]
+pub struct CommitDiff {
+    pub info: CommitInfo,
+    pub files: Vec<FilePatch>,
+    /// Files changed without a patch in the response, to be fetched whole at this commit.
+    pub unpatched: Vec<UnpatchedFile>,
+    /// The commit changes more files than the API lists (3000); the rest are missing.
+    pub truncated: bool,
+}
+
+#[derive(Debug)]
+pub enum GitHubError {
+    /// 401, or 403 without rate limit: bad credentials or missing permission.
+    Auth { route: String, message: String },
+    NotFound { route: String },
+    /// Still rate limited after every retry.
+    RateLimit { route: String },
+    /// A response or file content that could not be decoded.
+    Decode { route: String, message: String },
+    /// A file or listing over the size the API serves.
+    TooLarge { route: String, message: String },
+    /// The request could not be sent or got no response, even after retries.
+    Network { route: String, message: String },
+    /// Any other error status.
+    Api { route: String, status: StatusCode, message: String },
+    /// Invalid API URL, CA certificate, app key or installation.
+    Config(String),
+}
+
+impl GitHubError {
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            GitHubError::Auth { .. } => ErrorKind::Auth,
+            GitHubError::NotFound { .. } => ErrorKind::NotFound,
+            GitHubError::RateLimit { .. } => ErrorKind::RateLimit,
+            GitHubError::Decode { .. } => ErrorKind::Decode,
+            GitHubError::TooLarge { .. } => ErrorKind::TooLarge,
+            GitHubError::Network { .. } => ErrorKind::Network,
+            GitHubError::Api { .. } => ErrorKind::Api,
+            GitHubError::Config(_) => ErrorKind::Config,
+        }
+    }
+
+    fn from_octocrab(route: &str, error: octocrab::Error) -> Self {
+        let route = route.to_string();
+        let message = describe(&error);
+        match error {
+            octocrab::Error::GitHub { source, .. } => match source.status_code {
+                StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => GitHubError::Auth { route, message },
+                StatusCode::NOT_FOUND => GitHubError::NotFound { route },
+                status => GitHubError::Api { route, status, message },
+            },
+            // Signing the app's JWT or fetching its installation token.
+            octocrab::Error::JWT { .. } | octocrab::Error::Installation { .. } => GitHubError::Auth { route, message },
+            octocrab::Error::Json { .. } | octocrab::Error::Serde { .. } | octocrab::Error::InvalidUtf8 { .. } => {
+                GitHubError::Decode { route, message }
+            }
+            _ => GitHubError::Network { route, message },
+        }
+    }
+}
+
+impl fmt::Display for GitHubError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GitHubError::Auth { route, message } => write!(f, "access denied on {}: {}", route, message),
+            GitHubError::NotFound { route } => write!(f, "not found: {}", route),
+            GitHubError::RateLimit { route } => {
+                write!(f, "rate limit still exceeded on {} after {} retries", route, MAX_RETRIES)
+            }
+            GitHubError::Decode { route, message } => write!(f, "cannot decode {}: {}", route, message),
+            GitHubError::TooLarge { route, message } => {
+                write!(f, "too large for the GitHub API: {}: {}", route, message)
+            }
+            GitHubError::Network { route, message } => write!(f, "request to {} failed: {}", route, message),
+            GitHubError::Api { route, status, message } => {
+                write!(f, "GitHub API error {} on {}: {}", status, route, message)
+            }
+            GitHubError::Config(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for GitHubError {}
+
+/// How the client authenticates.
+pub enum Credentials {
+    /// Personal access token (`GITHUB_TOKEN`).
+    Token(String),
+    /// GitHub App: requests use an installation token, obtained with a JWT signed by
+    /// the app's private key and renewed shortly before it expires (after one hour).
+    App {
+        app_id: u64,
+        private_key: EncodingKey,
+        /// Found automatically when the app is installed on a single account.
+        installation_id: Option<u64>,
+    },
+}
+
+impl Credentials {
+    /// Reads the app's RSA private key from its PEM file.
+    pub fn app(app_id: u64, key_file: &Path, installation_id: Option<u64>) -> Result<Self, GitHubError> {
+        let pem = std::fs::read(key_file).map_err(|e| {
+            GitHubError::Config(format!("cannot read private key {}: {}", key_file.display(), e))
+        })?;
+        let private_key = EncodingKey::from_rsa_pem(&pem).map_err(|e| {
+            GitHubError::Config(format!("invalid private key {}: {}", key_file.display(), e))
+        })?;
+        Ok(Credentials::App {
+            app_id,
+            private_key,
+            installation_id,
+        })
+    }
 }
 
+pub const DEFAULT_API_URL: &str = "https://api.github.com";
+
+const RAW_MEDIA_TYPE: &str = "application/vnd.github.raw";
+const MAX_RETRIES: u32 = 5;
+/// Files per page of a commit, and the most GitHub lists across pages.
+const COMMIT_FILES_PER_PAGE: usize = 300;
+const MAX_COMMIT_FILES: usize = 3000;
+const BASE_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
 pub struct GitHubClient {
     client: Octocrab,
+    /// Root of the web UI matching the API, for links in reports.
+    web_url: String,
+    /// Authenticated as a GitHub App installation rather than a user.
+    installation: bool,
+    // Set when the primary rate limit is exhausted, so concurrent requests all pause.
+    paused_until: Mutex<Option<Instant>>,
+    waits: Mutex<WaitClock>,
+}
+
+/// Wall-clock time during which at least one request was sleeping, so
+/// concurrent tasks waiting on the same pause are counted once.
+#[derive(Default)]
+struct WaitClock {
+    sleeping: usize,
+    since: Option<Instant>,
+    total: Duration,
 }
 
 impl GitHubClient {
-    pub fn new(token: &str) -> Result<Self, Box<dyn std::error::Error>> {
-        let client = Octocrab::builder()
-            .personal_token(token.to_string())
-            .build()?;
-        Ok(Self { client })
+    /// `api_url` is `https://api.github.com` or the API root of a GitHub Enterprise
+    /// Server (`https://github.example.com/api/v3`). `ca_cert` is a PEM file of extra
+    /// root certificates trusted on top of the system ones.
+    pub async fn new(
+        credentials: &Credentials,
+        api_url: &str,
+        ca_cert: Option<&Path>,
+    ) -> Result<Self, GitHubError> {
+        let api_url = api_url.trim_end_matches('/');
+        let base_uri = api_url
+            .parse::<Uri>()
+            .ok()
+            .filter(|uri| matches!(uri.scheme_str(), Some("http" | "https")) && uri.authority().is_some())
+            .ok_or_else(|| {
+                GitHubError::Config(format!("invalid API URL '{}': expected http(s)://host[/path]", api_url))
+            })?;
+
+        let mut github = Self {
+            client: build_octocrab(credentials, base_uri, ca_cert)?,
+            web_url: web_url(api_url),
+            installation: false,
+            paused_until: Mutex::new(None),
+            waits: Mutex::new(WaitClock::default()),
+        };
+
+        // The app's JWT can only manage installations; the API itself is used
+        // with the token of one installation.
+        if let Credentials::App { installation_id, .. } = credentials {
+            let id = match installation_id {
+                Some(id) => *id,
+                None => github.find_installation().await?,
+            };
+            github.client = github.client.installation(InstallationId(id)).map_err(config_error)?;
+            github.installation = true;
+        }
+
+        Ok(github)
     }
 
-    pub async fn list_repos(&self) -> Result<Vec<RepoInfo>, Box<dyn std::error::Error>> {
+    /// The app's only installation; several of them need an explicit ID.
+    async fn find_installation(&self) -> Result<u64, GitHubError> {
+        let installations: Vec<ApiInstallation> = self.get_json("/app/installations?per_page=100").await?;
+
+        match installations.as_slice() {
+            [] => Err(GitHubError::Config("the GitHub App is not installed on any account".to_string())),
+            [installation] => Ok(installation.id),
+            _ => {
+                let choices: Vec<String> = installations
+                    .iter()
+                    .map(|i| match &i.account {
+                        Some(account) => format!("{} ({})", i.id, account.login),
+                        None => i.id.to_string(),
+                    })
+                    .collect();
+                Err(GitHubError::Config(format!(
+                    "the GitHub App has several installations, pick one with --installation-id: {}",
+                    choices.join(", ")
+                )))
+            }
+        }
+    }
+
+    /// Link to a line of a file at a branch, tag or commit, on the web UI of the API's host.
+    pub fn blob_url(&self, owner: &str, repo: &str, git_ref: &str, path: &str, line: usize) -> String {
+        format!(
+            "{}/{}/{}/blob/{}/{}#L{}",
+            self.web_url,
+            owner,
+            repo,
+            encode_path(git_ref),
+            encode_path(path),
+            line
+        )
+    }
+
+    /// Wall-clock time during which requests were paused by rate limits or retry backoff.
+    pub fn rate_limit_wait(&self) -> Duration {
+        let waits = self.waits.lock().unwrap();
+        match waits.since {
+            Some(since) => waits.total + since.elapsed(),
+            None => waits.total,
+        }
+    }
+
+    /// GETs a JSON route; see [`Self::get`].
+    async fn get_json<T: DeserializeOwned>(&self, route: &str) -> Result<T, GitHubError> {
+        let body = self.get(route, None).await?;
+        serde_json::from_slice(&body).map_err(|e| GitHubError::Decode {
+            route: route.to_string(),
+            message: e.to_string(),
+        })
+    }
+
+    /// GETs a route, honoring rate-limit headers and retrying 403/429/5xx
+    /// responses and network errors with exponential backoff. `accept`
+    /// overrides the media type, e.g. to get a file's raw bytes.
+    async fn get(&self, route: &str, accept: Option<&'static str>) -> Result<Vec<u8>, GitHubError> {
+        let mut attempt = 0;
+        let request_headers = accept.map(|media_type| HeaderMap::from_iter([(ACCEPT, HeaderValue::from_static(media_type))]));
+
+        loop {
+            self.wait_for_rate_limit().await;
+
+            let response = match self.client._get_with_headers(route, request_headers.clone()).await {
+                Ok(r) => r,
+                Err(e) => {
+                    let error = GitHubError::from_octocrab(route, e);
+                    if !matches!(error, GitHubError::Network { .. }) || attempt >= MAX_RETRIES {
+                        return Err(error);
+                    }
+                    self.sleep(backoff(attempt)).await;
+                    attempt += 1;
+                    continue;
+                }
+            };
+
+            let status = response.status();
+            let headers = response.headers().clone();
+            self.record_rate_limit(&headers);
+
+            if status.is_success() {
+                return match response.into_body().collect().await {
+                    Ok(body) => Ok(body.to_bytes().to_vec()),
+                    Err(e) => Err(GitHubError::from_octocrab(route, e)),
+                };
+            }
+
+            let body = self.client.body_to_string(response).await.unwrap_or_default();
+            let body = serde_json::from_str::<ApiErrorBody>(&body).ok();
+            let too_large = body
+                .as_ref()
+                .is_some_and(|b| b.errors.iter().any(|e| e.code.as_deref() == Some("too_large")));
+            let message = body.and_then(|b| b.message).unwrap_or_default();
+
+            let retryable = is_retryable(status, &headers, &message);
+            if retryable && attempt < MAX_RETRIES {
+                let delay = retry_delay(&headers).unwrap_or_else(|| backoff(attempt));
+                self.sleep(delay).await;
+                attempt += 1;
+                continue;
+            }
+
+            let route = route.to_string();
+
+            return Err(match status {
+                _ if too_large => GitHubError::TooLarge { route, message },
+                StatusCode::PAYLOAD_TOO_LARGE => GitHubError::TooLarge { route, message },
+                // Only 403/429 are rate limits; a 5xx that persists is reported as is.
+                StatusCode::FORBIDDEN | StatusCode::TOO_MANY_REQUESTS if retryable => {
+                    GitHubError::RateLimit { route }
+                }
+                StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => GitHubError::Auth { route, message },
+                StatusCode::NOT_FOUND => GitHubError::NotFound { route },
+                _ => GitHubError::Api { route, status, message },
+            });
+        }
+    }
+
+    async fn wait_for_rate_limit(&self) {
+        let paused_until = *self.paused_until.lock().unwrap();
+        if let Some(until) = paused_until {
+            let now = Instant::now();
+            if until > now {
+                self.sleep(until - now).await;
+            }
+        }
+    }
+
+    fn record_rate_limit(&self, headers: &HeaderMap) {
+        if header_u64(headers, "x-ratelimit-remaining") != Some(0) {
+            return;
+        }
+
+        if let Some(delay) = until_reset(headers) {
+            let until = Instant::now() + delay;
+            let mut paused_until = self.paused_until.lock().unwrap();
+            if paused_until.is_none_or(|current| current < until) {
+                *paused_until = Some(until);
+            }
+        }
+    }
+
+    async fn sleep(&self, duration: Duration) {
+        {
+            let mut waits = self.waits.lock().unwrap();
+            if waits.sleeping == 0 {
+                waits.since = Some(Instant::now());
+            }
+            waits.sleeping += 1;
+        }
+
+        tokio::time::sleep(duration).await;
+
+        let mut waits = self.waits.lock().unwrap();
+        waits.sleeping -= 1;
+        if waits.sleeping == 0
+            && let Some(since) = waits.since.take()
+        {
+            waits.total += since.elapsed();
+        }
+    }
+
+    /// Repositories the authenticated user can access, including private ones,
+    /// or every repository granted to the app installation.
+    pub async fn list_repos(&self) -> Result<Vec<RepoInfo>, GitHubError> {
+        if self.installation {
+            self.list_repos_at("/installation/repositories?").await
+        } else {
+            self.list_repos_at("/user/repos?").await
+        }
+    }
+
+    /// Every repository of an organization visible to the token.
+    pub async fn list_org_repos(&self, org: &str) -> Result<Vec<RepoInfo>, GitHubError> {
+        self.list_repos_at(&format!("/orgs/{}/repos?type=all&", org)).await
+    }
+
+    /// Repositories owned by a user; only public ones unless it is the token's user.
+    pub async fn list_user_repos(&self, login: &str) -> Result<Vec<RepoInfo>, GitHubError> {
+        self.list_repos_at(&format!("/users/{}/repos?type=owner&", login)).await
+    }
+
+    /// Pages through a repository listing; `route` ends with `?` or `&`.
+    async fn list_repos_at(&self, route: &str) -> Result<Vec<RepoInfo>, GitHubError> {
         let mut repos = Vec::new();
-        let mut page = 1u8;
+        let mut page = 1;
 
         loop {
-            let result = self
-                .client
-                .current()
-                .list_repos_for_authenticated_user()
-                .per_page(100)
-                .page(page)
-                .send()
-                .await?;
-
-            if result.items.is_empty() {
+            let route = format!("{}per_page=100&page={}", route, page);
+            let items = match self.get_json(&route).await? {
+                ApiRepoPage::List(items) => items,
+                ApiRepoPage::Installation { repositories } => repositories,
+            };
+
+            if items.is_empty() {
                 break;
             }
 
-            for repo in result.items {
+            let last_page = items.len() < 100;
+            for repo in items {
+                let visibility = repo.visibility.unwrap_or_else(|| {
+                    if repo.private { "private" } else { "public" }.to_string()
+                });
                 repos.push(RepoInfo {
-                    name: repo.name.clone(),
-                    full_name: repo.full_name.unwrap_or(repo.name),
-                    private: repo.private.unwrap_or(false),
+                    full_name: repo.full_name.unwrap_or_else(|| repo.name.clone()),
+                    name: repo.name,
+                    private: repo.private,
+                    visibility,
                     default_branch: repo.default_branch.unwrap_or_else(|| "main".to_string()),
+                    archived: repo.archived,
+                    fork: repo.fork,
+                    topics: repo.topics,
+                    pushed_at: repo.pushed_at,
                 });
             }
 
-            page += 1;
-            if page > 100 {
+            if last_page {
                 break;
             }
+            page += 1;
         }
 
         Ok(repos)
@@ -68,27 +604,22 @@ impl GitHubClient {
         &self,
         owner: &str,
         repo: &str,
-    ) -> Result<Vec<BranchInfo>, Box<dyn std::error::Error>> {
+    ) -> Result<Vec<BranchInfo>, GitHubError> {
         let mut branches = Vec::new();
-        let mut page = 1u8;
+        let mut page = 1;
 
         loop {
-            let result = self
-                .client
-                .repos(owner, repo)
-                .list_branches()
-                .per_page(100)
-                .page(page)
-                .send()
-                .await?;
-
-            if result.items.is_empty() {
+            let route = format!("/repos/{}/{}/branches?per_page=100&page={}", owner, repo, page);
+            let items: Vec<ApiBranch> = self.get_json(&route).await?;
+
+            if items.is_empty() {
                 break;
             }
 
-            for branch in result.items {
+            for branch in items {
                 branches.push(BranchInfo {
                     name: branch.name,
+                    commit_sha: branch.commit.sha,
                 });
             }
 
@@ -105,107 +636,393 @@ impl GitHubClient {
         &self,
         owner: &str,
         repo: &str,
-        path: &str,
-        branch: Option<&str>,
-    ) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+        tree_sha: &str,
+    ) -> Result<Vec<RepoFile>, GitHubError> {
+        let tree = self.get_tree(owner, repo, tree_sha, true).await?;
         let mut files = Vec::new();
-        self.get_files_recursive(owner, repo, path, branch, &mut files).await?;
+
+        if tree.truncated {
+            self.walk_tree(owner, repo, tree_sha, "", &mut files).await?;
+        } else {
+            collect_files(tree.tree, "", &mut files);
+        }
+
         Ok(files)
     }
 
-    async fn get_files_recursive(
+    /// Fallback for trees too large for a single recursive listing: lists one level,
+    /// then requests each subtree recursively, descending further only where GitHub
+    /// truncates again.
+    async fn walk_tree(
         &self,
         owner: &str,
         repo: &str,
-        path: &str,
-        branch: Option<&str>,
-        files: &mut Vec<String>,
-    ) -> Result<(), Box<dyn std::error::Error>> {
-        let repo_handler = self.client.repos(owner, repo);
-        let content = match branch {
-            Some(b) => repo_handler.get_content().path(path).r#ref(b).send().await?,
-            None => repo_handler.get_content().path(path).send().await?,
-        };
+        tree_sha: &str,
+        prefix: &str,
+        files: &mut Vec<RepoFile>,
+    ) -> Result<(), GitHubError> {
+        let tree = self.get_tree(owner, repo, tree_sha, false).await?;
 
-        if content.items.len() == 1 && content.items[0].r#type == "file" {
-            files.push(path.to_string());
-        } else {
-            for item in &content.items {
-                let item_path = item.path.clone();
-                match item.r#type.as_str() {
-                    "file" => {
-                        if Self::is_scannable_file(&item_path) {
-                            files.push(item_path);
-                        }
-                    }
-                    "dir" => {
-                        if !Self::should_skip_dir(&item_path) {
-                            Box::pin(self.get_files_recursive(owner, repo, &item_path, branch, files)).await?;
-                        }
+        for entry in tree.tree {
+            let path = join_path(prefix, &entry.path);
+            match entry.r#type.as_str() {
+                "blob" if is_scannable_file(&path) => {
+                    files.push(RepoFile {
+                        path,
+                        sha: entry.sha,
+                        size: entry.size,
+                    });
+                }
+                "tree" if !should_skip_dir(&path) => {
+                    let subtree = self.get_tree(owner, repo, &entry.sha, true).await?;
+                    if subtree.truncated {
+                        Box::pin(self.walk_tree(owner, repo, &entry.sha, &path, files)).await?;
+                    } else {
+                        collect_files(subtree.tree, &path, files);
                     }
-                    _ => {}
                 }
+                _ => {}
             }
         }
 
         Ok(())
     }
 
-    fn is_scannable_file(path: &str) -> bool {
-        let scannable_extensions = [
-            ".rs", ".py", ".js", ".ts", ".jsx", ".tsx", ".go", ".java", ".rb", ".php",
-            ".cs", ".cpp", ".c", ".h", ".hpp", ".swift", ".kt", ".scala", ".sh", ".bash",
-            ".env", ".yml", ".yaml", ".json", ".toml", ".xml", ".ini", ".cfg", ".conf",
-            ".properties", ".md", ".txt", ".sql", ".dockerfile", ".tf", ".tfvars",
-        ];
-        
-        let path_lower = path.to_lowercase();
-        
-        let secret_filenames = [
-            ".env", ".env.local", ".env.development", ".env.production",
-            "credentials", "secrets", "config", ".npmrc", ".pypirc",
-        ];
-        
-        for filename in secret_filenames {
-            if path_lower.ends_with(filename) {
-                return true;
-            }
-        }
-        
-        scannable_extensions.iter().any(|ext| path_lower.ends_with(ext))
-    }
-
-    fn should_skip_dir(path: &str) -> bool {
-        let skip_dirs = [
-            "node_modules", ".git", "vendor", "target", "dist", "build",
-            "__pycache__", ".venv", "venv", ".idea", ".vscode", "coverage",
-            ".next", ".nuxt", "out", "bin", "obj", "packages",
-        ];
-        
-        skip_dirs.iter().any(|dir| path.contains(dir))
-    }
-
-    pub async fn get_file_content(
+    async fn get_tree(
         &self,
         owner: &str,
         repo: &str,
-        path: &str,
-        branch: Option<&str>,
-    ) -> Result<String, Box<dyn std::error::Error>> {
-        let repo_handler = self.client.repos(owner, repo);
-        let content = match branch {
-            Some(b) => repo_handler.get_content().path(path).r#ref(b).send().await?,
-            None => repo_handler.get_content().path(path).send().await?,
+        tree_sha: &str,
+        recursive: bool,
+    ) -> Result<TreeResponse, GitHubError> {
+        let mut route = format!("/repos/{}/{}/git/trees/{}", owner, repo, tree_sha);
+        if recursive {
+            route.push_str("?recursive=1");
+        }
+        self.get_json(&route).await
+    }
+
+    pub async fn get_blob_content(
+        &self,
+        owner: &str,
+        repo: &str,
+        blob_sha: &str,
+    ) -> Result<String, GitHubError> {
+        let route = format!("/repos/{}/{}/git/blobs/{}", owner, repo, blob_sha);
+        let blob: BlobResponse = self.get_json(&route).await?;
+        self.blob_text(route, blob).await
+    }
+
+    /// Like [`Self::get_blob_content`] for a blob whose size is not known yet: one
+    /// for which `too_large(size)` holds is neither decoded nor downloaded raw.
+    pub async fn get_blob_content_within(
+        &self,
+        owner: &str,
+        repo: &str,
+        blob_sha: &str,
+        too_large: impl Fn(u64) -> bool,
+    ) -> Result<BlobContent, GitHubError> {
+        let route = format!("/repos/{}/{}/git/blobs/{}", owner, repo, blob_sha);
+        let blob: BlobResponse = self.get_json(&route).await?;
+
+        if too_large(blob.size) {
+            return Ok(BlobContent::TooLarge { size: blob.size });
+        }
+        Ok(BlobContent::Text(self.blob_text(route, blob).await?))
+    }
+
+    async fn blob_text(&self, route: String, blob: BlobResponse) -> Result<String, GitHubError> {
+        // GitHub leaves `content` empty when a file is too big to inline in JSON;
+        // the raw media type still serves it.
+        if blob.content.is_empty() && blob.size > 0 {
+            let raw = self.get(&route, Some(RAW_MEDIA_TYPE)).await?;
+            return Ok(String::from_utf8_lossy(&raw).to_string());
+        }
+
+        if blob.encoding != "base64" {
+            return Ok(blob.content);
+        }
+
+        let cleaned: String = blob.content.chars().filter(|c| !c.is_whitespace()).collect();
+        let decoded = STANDARD.decode(&cleaned).map_err(|e| GitHubError::Decode {
+            route,
+            message: e.to_string(),
+        })?;
+        Ok(String::from_utf8_lossy(&decoded).to_string())
+    }
+
+    pub async fn list_commits(
+        &self,
+        owner: &str,
+        repo: &str,
+        head_sha: &str,
+    ) -> Result<Vec<String>, GitHubError> {
+        let mut commits = Vec::new();
+        let mut page = 1;
+
+        loop {
+            let route = format!(
+                "/repos/{}/{}/commits?sha={}&per_page=100&page={}",
+                owner, repo, head_sha, page
+            );
+            let items: Vec<ApiCommitRef> = self.get_json(&route).await?;
+
+            if items.is_empty() {
+                break;
+            }
+
+            let last_page = items.len() < 100;
+            commits.extend(items.into_iter().map(|c| c.sha));
+
+            if last_page {
+                break;
+            }
+            page += 1;
+        }
+
+        Ok(commits)
+    }
+
+    pub async fn get_commit(
+        &self,
+        owner: &str,
+        repo: &str,
+        sha: &str,
+    ) -> Result<CommitDiff, GitHubError> {
+        let route = format!("/repos/{}/{}/commits/{}", owner, repo, sha);
+        let mut commit: ApiCommit = self.get_json(&route).await?;
+
+        // The changed files are paginated, 300 per page.
+        let mut page_len = commit.files.len();
+        let mut page = 1;
+        while page_len >= COMMIT_FILES_PER_PAGE && commit.files.len() < MAX_COMMIT_FILES {
+            page += 1;
+            let next: ApiCommit = self.get_json(&format!("{}?page={}", route, page)).await?;
+            page_len = next.files.len();
+            commit.files.extend(next.files);
+        }
+        let truncated = commit.files.len() >= MAX_COMMIT_FILES;
+
+        let (author, date) = match commit.commit.author {
+            Some(a) => (a.name, a.date.unwrap_or_default()),
+            None => (String::new(), String::new()),
+        };
+
+        let mut files = Vec::new();
+        let mut unpatched = Vec::new();
+        for file in commit.files {
+            match (file.patch, file.sha) {
+                (Some(patch), _) => files.push(FilePatch {
+                    path: file.filename,
+                    patch,
+                }),
+                (None, Some(sha)) if file.status != "removed" => unpatched.push(UnpatchedFile {
+                    path: file.filename,
+                    sha,
+                }),
+                _ => {}
+            }
+        }
+
+        Ok(CommitDiff {
+            info: CommitInfo {
+                sha: commit.sha,
+                author,
+                date,
+            },
+            files,
+            unpatched,
+            truncated,
+        })
+    }
+}
+
+fn collect_files(entries: Vec<TreeEntry>, prefix: &str, files: &mut Vec<RepoFile>) {
+    for entry in entries {
+        if entry.r#type != "blob" {
+            continue;
+        }
+
+        let path = join_path(prefix, &entry.path);
+        if !in_skipped_dir(&path) && is_scannable_file(&path) {
+            files.push(RepoFile {
+                path,
+                sha: entry.sha,
+                size: entry.size,
+            });
+        }
+    }
+}
+
+fn join_path(prefix: &str, path: &str) -> String {
+    if prefix.is_empty() {
+        path.to_string()
+    } else {
+        format!("{}/{}", prefix, path)
+    }
+}
+
+fn is_retryable(status: StatusCode, headers: &HeaderMap, message: &str) -> bool {
+    match status {
+        StatusCode::TOO_MANY_REQUESTS => true,
+        // 403 is only a rate limit when GitHub says so; otherwise it is a permission error.
+        StatusCode::FORBIDDEN => {
+            header_u64(headers, "x-ratelimit-remaining") == Some(0)
+                || headers.contains_key("retry-after")
+                || is_secondary_rate_limit(message)
+        }
+        s => s.is_server_error(),
+    }
+}
+
+/// Secondary rate limits do not always come with `retry-after`; the message names them.
+fn is_secondary_rate_limit(message: &str) -> bool {
+    message.to_ascii_lowercase().contains("secondary rate limit")
+}
+
+fn retry_delay(headers: &HeaderMap) -> Option<Duration> {
+    if let Some(seconds) = header_u64(headers, "retry-after") {
+        return Some(Duration::from_secs(seconds));
+    }
+    if header_u64(headers, "x-ratelimit-remaining") == Some(0) {
+        return until_reset(headers);
+    }
+    None
+}
+
+fn until_reset(headers: &HeaderMap) -> Option<Duration> {
+    let reset = header_u64(headers, "x-ratelimit-reset")?;
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).ok()?.as_secs();
+    // One extra second absorbs clock skew with GitHub.
+    Some(Duration::from_secs(reset.saturating_sub(now) + 1))
+}
+
+fn build_octocrab(credentials: &Credentials, base_uri: Uri, ca_cert: Option<&Path>) -> Result<Octocrab, GitHubError> {
+    let Some(ca_cert) = ca_cert else {
+        let builder = Octocrab::builder()
+            .base_uri(base_uri)
+            .map_err(config_error)?
+            .add_retry_config(RetryConfig::None);
+        let client = match credentials {
+            Credentials::Token(token) => builder.personal_token(token.clone()).build(),
+            Credentials::App { app_id, private_key, .. } => builder.app(AppId(*app_id), private_key.clone()).build(),
         };
+        return client.map_err(config_error);
+    };
+
+    let (auth_header, auth_state) = match credentials {
+        Credentials::Token(token) => {
+            let header = format!("Bearer {}", token).parse().map_err(config_error)?;
+            (Some(header), AuthState::None)
+        }
+        Credentials::App { app_id, private_key, .. } => (
+            None,
+            AuthState::App(AppAuth {
+                app_id: AppId(*app_id),
+                key: private_key.clone(),
+            }),
+        ),
+    };
+    build_with_ca(auth_header, auth_state, base_uri, ca_cert)
+}
+
+/// Same stack as `Octocrab::builder()`, but with a TLS connector that also trusts the
+/// certificates of `ca_cert`, e.g. the internal CA of a GitHub Enterprise Server.
+fn build_with_ca(
+    auth_header: Option<HeaderValue>,
+    auth_state: AuthState,
+    base_uri: Uri,
+    ca_cert: &Path,
+) -> Result<Octocrab, GitHubError> {
+    let invalid = |message: String| GitHubError::Config(format!("invalid CA certificate {}: {}", ca_cert.display(), message));
+
+    let mut roots = RootCertStore::empty();
+    roots.add_parsable_certificates(rustls_native_certs::load_native_certs().certs);
+
+    let certs = CertificateDer::pem_file_iter(ca_cert)
+        .and_then(|certs| certs.collect::<Result<Vec<_>, _>>())
+        .map_err(|e| invalid(e.to_string()))?;
+    if certs.is_empty() {
+        return Err(invalid("no certificate found".to_string()));
+    }
+    for cert in certs {
+        roots.add(cert).map_err(|e| invalid(e.to_string()))?;
+    }
+
+    let tls = ClientConfig::builder().with_root_certificates(roots).with_no_client_auth();
+    let connector = HttpsConnectorBuilder::new()
+        .with_tls_config(tls)
+        .https_or_http()
+        .enable_http1()
+        .build();
+    let client = Client::builder(TokioExecutor::new()).build(connector);
+
+    let headers = vec![(USER_AGENT, HeaderValue::from_static("mini-guardian"))];
+
+    OctocrabBuilder::new_empty()
+        .with_service(client)
+        .with_layer(&ExtraHeadersLayer::new(Arc::new(headers)))
+        .with_layer(&BaseUriLayer::new(base_uri.clone()))
+        .with_layer(&AuthHeaderLayer::new(auth_header, base_uri.clone(), base_uri))
+        .with_auth(auth_state)
+        .build()
+        .map_err(config_error)
+}
 
-        if let Some(item) = content.items.first() {
-            if let Some(ref encoded_content) = item.content {
-                let cleaned: String = encoded_content.chars().filter(|c| !c.is_whitespace()).collect();
-                let decoded = STANDARD.decode(&cleaned)?;
-                return Ok(String::from_utf8_lossy(&decoded).to_string());
+fn config_error(error: impl std::error::Error) -> GitHubError {
+    GitHubError::Config(describe(&error))
+}
+
+/// An error and its sources on one line, without the backtrace octocrab appends
+/// to its own messages.
+fn describe(error: &dyn std::error::Error) -> String {
+    let first_line = |e: &dyn std::error::Error| e.to_string().lines().next().unwrap_or_default().to_string();
+
+    let mut message = first_line(error);
+    let mut source = error.source();
+    while let Some(e) = source {
+        let line = first_line(e);
+        if !message.contains(&line) {
+            message = format!("{}: {}", message, line);
+        }
+        source = e.source();
+    }
+    message
+}
+
+/// Web UI root for an API URL: `api.github.com` -> `github.com`, a GitHub Enterprise
+/// Server's `https://host/api/v3` -> `https://host`, `api.<sub>.ghe.com` -> `<sub>.ghe.com`.
+/// Any other URL (a mock server...) is used as is.
+fn web_url(api_url: &str) -> String {
+    if let Some(root) = api_url.strip_suffix("/api/v3") {
+        return root.to_string();
+    }
+    if let Some((scheme, rest)) = api_url.split_once("://")
+        && let Some(host) = rest.strip_prefix("api.")
+    {
+        return format!("{}://{}", scheme, host);
+    }
+    api_url.to_string()
+}
+
+/// Percent-encodes a path for a URL, keeping `/` separators.
+fn encode_path(path: &str) -> String {
+    let mut encoded = String::with_capacity(path.len());
+    for byte in path.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => {
+                encoded.push(byte as char)
             }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
         }
-        
-        Ok(String::new())
     }
+    encoded
+}
+
+fn backoff(attempt: u32) -> Duration {
+    (BASE_BACKOFF * 2u32.pow(attempt)).min(MAX_BACKOFF)
+}
+
+fn header_u64(headers: &HeaderMap, name: &str) -> Option<u64> {
+    headers.get(name)?.to_str().ok()?.trim().parse().ok()
 }
@@ -0,0 +1,103 @@
+use std::fs;
+use std::path::Path;
+use walkdir::WalkDir;
+
+use crate::error::{ErrorKind, ScanError};
+use crate::paths::{is_scannable_file, should_skip_dir};
+use crate::scanner::{Finding, Scanner, SkippedFile};
+
+pub struct LocalScan {
+    pub findings: Vec<Finding>,
+    pub total_files: usize,
+    /// Entries that could not be read; any means a partial scan.
+    pub errors: Vec<ScanError>,
+    /// Files over the size limit, not read.
+    pub skipped: Vec<SkippedFile>,
+}
+
+pub fn scan_path(root: &Path, scanner: &Scanner) -> Result<LocalScan, Box<dyn std::error::Error>> {
+    if !root.exists() {
+        return Err(format!("path '{}' does not exist", root.display()).into());
+    }
+
+    let mut findings = Vec::new();
+    let mut total_files = 0;
+    let mut errors = Vec::new();
+    let mut skipped = Vec::new();
+
+    let walker = WalkDir::new(root)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|entry| {
+            entry.depth() == 0
+                || !entry.file_type().is_dir()
+                || !should_skip_dir(&relative_path(root, entry.path()))
+        });
+
+    for entry in walker {
+        let entry = match entry {
+            Ok(e) => e,
+            Err(e) => {
+                let target = e.path().map_or_else(|| root.display().to_string(), |p| relative_path(root, p));
+                errors.push(ScanError::new(target, ErrorKind::Io, e));
+                continue;
+            }
+        };
+        if !entry.file_type().is_file() {
+            continue;
+        }
+
+        let file_path = relative_path(root, entry.path());
+        if !is_scannable_file(&file_path) || !scanner.in_scope(&file_path) {
+            continue;
+        }
+
+        let size = match entry.metadata() {
+            Ok(m) => m.len(),
+            Err(e) => {
+                errors.push(ScanError::new(file_path, ErrorKind::Io, e));
+                continue;
+            }
+        };
+        if scanner.too_large(size) {
+            skipped.push(SkippedFile { path: file_path, size });
+            continue;
+        }
+
+        let bytes = match fs::read(entry.path()) {
+            Ok(b) => b,
+            Err(e) => {
+                errors.push(ScanError::new(file_path, ErrorKind::Io, e));
+                continue;
+            }
+        };
+
+        total_files += 1;
+        let content = String::from_utf8_lossy(&bytes);
+        findings.extend(scanner.scan_content(&file_path, &content));
+    }
+
+    Ok(LocalScan {
+        findings,
+        total_files,
+        errors,
+        skipped,
+    })
+}
+
+fn relative_path(root: &Path, path: &Path) -> String {
+    let relative = path.strip_prefix(root).unwrap_or(path);
+    if relative.as_os_str().is_empty() {
+        // A single file passed as root has no relative component.
+        return path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+    }
+
+    relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
@@ -1,16 +1,45 @@
+mod baseline;
+mod config;
+mod diff;
+mod error;
+mod filters;
+mod git;
 mod github;
+mod local;
+mod paths;
 mod patterns;
+mod remote;
 mod reporter;
 mod scanner;
+mod validators;
+mod verify;
 
-use clap::{Parser, Subcommand};
+use clap::{Args, Parser, Subcommand, ValueEnum};
 use colored::*;
 use dotenv::dotenv;
 use std::env;
+use futures::future::join_all;
+use std::collections::HashSet;
+use std::path::PathBuf;
+use tokio::sync::Semaphore;
 
-use github::GitHubClient;
-use reporter::{print_findings, print_findings_json, print_scan_summary};
-use scanner::Scanner;
+use baseline::Baseline;
+use error::{ErrorKind, ScanError};
+use config::Config;
+use github::{Credentials, GitHubClient, GitHubError, RepoInfo};
+use patterns::Severity;
+use reporter::{
+    print_filtered_findings, print_findings, print_findings_json, print_findings_sarif, print_scan_errors,
+    print_scan_summary, ScanSummary,
+};
+use filters::PlaceholderFilter;
+use scanner::{EntropyConfig, Finding, Scanner, SkippedFile, Suppression, ENTROPY_BASE64_TYPE, ENTROPY_HEX_TYPE};
+use verify::{SecretVerifier, Service};
+
+/// Findings at or above `--fail-on` were reported.
+const EXIT_FINDINGS: i32 = 1;
+/// The scan failed or was partial (unreadable files, API errors, bad arguments...).
+const EXIT_ERROR: i32 = 2;
 
 #[derive(Parser)]
 #[command(name = "mini-guardian")]
@@ -20,6 +49,226 @@ use scanner::Scanner;
 struct Cli {
     #[command(subcommand)]
     command: Commands,
+
+    #[command(flatten)]
+    detection: DetectionArgs,
+
+    #[command(flatten)]
+    github: GitHubArgs,
+}
+
+#[derive(Args)]
+struct GitHubArgs {
+    /// GitHub API root, e.g. https://github.example.com/api/v3 for GitHub Enterprise Server
+    #[arg(long, value_name = "URL", env = "GITHUB_API_URL", global = true, default_value = github::DEFAULT_API_URL)]
+    api_url: String,
+
+    /// PEM file of extra CA certificates to trust for the GitHub API
+    #[arg(long, value_name = "FILE", global = true)]
+    ca_cert: Option<PathBuf>,
+
+    /// Authenticate as this GitHub App instead of with GITHUB_TOKEN
+    #[arg(long, value_name = "ID", env = "GITHUB_APP_ID", global = true, requires = "app_key")]
+    app_id: Option<u64>,
+
+    /// PEM private key of the GitHub App
+    #[arg(long, value_name = "FILE", env = "GITHUB_APP_PRIVATE_KEY_PATH", global = true, requires = "app_id")]
+    app_key: Option<PathBuf>,
+
+    /// Installation of the GitHub App, if it is installed on several accounts
+    #[arg(long, value_name = "ID", env = "GITHUB_APP_INSTALLATION_ID", global = true, requires = "app_id")]
+    installation_id: Option<u64>,
+}
+
+#[derive(Args)]
+struct DetectionArgs {
+    /// JSON config with include/exclude path globs, globally and per rule
+    #[arg(long, value_name = "FILE", global = true)]
+    config: Option<PathBuf>,
+
+    /// Additional rule file(s) applied on top of the bundled regex.json
+    #[arg(long = "rules", value_name = "FILE", global = true)]
+    rules: Vec<PathBuf>,
+
+    /// Also report high-entropy strings that match no rule
+    #[arg(long, global = true)]
+    entropy: bool,
+
+    /// Minimum Shannon entropy for base64-like strings
+    #[arg(long, value_name = "BITS", global = true, default_value_t = EntropyConfig::default().base64_threshold)]
+    entropy_base64: f64,
+
+    /// Minimum Shannon entropy for hex strings
+    #[arg(long, value_name = "BITS", global = true, default_value_t = EntropyConfig::default().hex_threshold)]
+    entropy_hex: f64,
+
+    /// Minimum length of a string checked for entropy
+    #[arg(long, value_name = "CHARS", global = true, default_value_t = EntropyConfig::default().min_length)]
+    entropy_min_length: usize,
+
+    /// File of extra placeholder stopwords, one per line
+    #[arg(long, value_name = "FILE", global = true)]
+    stopwords: Option<PathBuf>,
+
+    /// Report placeholders and example values too
+    #[arg(long, global = true, conflicts_with = "stopwords")]
+    no_placeholder_filter: bool,
+
+    /// Skip files larger than this (bytes, or with a K/M/G suffix); 0 scans every file
+    #[arg(long, value_name = "SIZE", global = true, default_value = "10M", value_parser = parse_size)]
+    max_file_size: u64,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum OutputFormat {
+    Text,
+    Json,
+    Sarif,
+}
+
+#[derive(Args)]
+struct OutputArgs {
+    /// Shorthand for --format json
+    #[arg(short, long)]
+    json: bool,
+
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    format: OutputFormat,
+
+    /// Only report findings that are not in this baseline file
+    #[arg(long, value_name = "FILE")]
+    baseline: Option<PathBuf>,
+
+    /// Write every current finding to the --baseline file instead of reporting them
+    #[arg(long, requires = "baseline")]
+    update_baseline: bool,
+
+    /// Also list the matches dropped as placeholders, with the reason
+    #[arg(long)]
+    show_filtered: bool,
+
+    /// Only report findings at or above this severity
+    #[arg(long, value_enum, value_name = "SEVERITY", default_value_t = Severity::Low)]
+    min_severity: Severity,
+
+    /// Exit with code 1 only if a reported finding is at or above this severity
+    #[arg(long, value_enum, value_name = "SEVERITY", default_value_t = Severity::Low)]
+    fail_on: Severity,
+
+    /// Check reported secrets against their service to tell if they are still active
+    #[arg(long)]
+    verify: bool,
+
+    /// Base URL of a verification service, e.g. github=http://localhost:8080
+    #[arg(long, value_name = "SERVICE=URL", value_parser = verify::parse_endpoint, requires = "verify")]
+    verify_endpoint: Vec<(Service, String)>,
+}
+
+impl OutputArgs {
+    fn format(&self) -> OutputFormat {
+        if self.json { OutputFormat::Json } else { self.format }
+    }
+
+    /// Progress messages are only printed for the text format, so that
+    /// JSON and SARIF output stay machine-readable.
+    fn quiet(&self) -> bool {
+        self.format() != OutputFormat::Text
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Visibility {
+    Public,
+    Private,
+    Internal,
+}
+
+/// Narrows the repositories of scan-all, scan-org and scan-user.
+#[derive(Args)]
+struct RepoFilterArgs {
+    /// Skip archived repositories
+    #[arg(long)]
+    skip_archived: bool,
+
+    /// Skip forked repositories
+    #[arg(long)]
+    skip_forks: bool,
+
+    /// Only scan repositories with this visibility
+    #[arg(long, value_enum)]
+    visibility: Option<Visibility>,
+
+    /// Only scan repositories tagged with this topic (repeatable: any of them)
+    #[arg(long = "topic", value_name = "TOPIC")]
+    topics: Vec<String>,
+
+    /// Only scan repositories pushed to on or after this date
+    #[arg(long, value_name = "YYYY-MM-DD", value_parser = parse_date)]
+    pushed_after: Option<String>,
+}
+
+/// Bounds the GitHub requests in flight during a scan.
+#[derive(Args)]
+struct ConcurrencyArgs {
+    /// Maximum number of concurrent GitHub requests
+    #[arg(long, default_value_t = 8, value_parser = clap::value_parser!(u16).range(1..))]
+    concurrency: u16,
+}
+
+impl ConcurrencyArgs {
+    fn limiter(&self) -> Semaphore {
+        Semaphore::new(self.concurrency as usize)
+    }
+}
+
+impl RepoFilterArgs {
+    fn matches(&self, repo: &RepoInfo) -> bool {
+        let visibility = self.visibility.map(|v| match v {
+            Visibility::Public => "public",
+            Visibility::Private => "private",
+            Visibility::Internal => "internal",
+        });
+
+        let excluded = (self.skip_archived && repo.archived) || (self.skip_forks && repo.fork);
+
+        !excluded
+            && visibility.is_none_or(|v| repo.visibility.eq_ignore_ascii_case(v))
+            && (self.topics.is_empty() || repo.topics.iter().any(|t| self.topics.contains(t)))
+            // ISO 8601 timestamps compare correctly as strings.
+            && self
+                .pushed_after
+                .as_deref()
+                .is_none_or(|date| repo.pushed_at.as_deref().is_some_and(|pushed| pushed >= date))
+    }
+}
+
+fn parse_date(value: &str) -> Result<String, String> {
+    let parts: Vec<&str> = value.split('-').collect();
+    let valid = parts.len() == 3
+        && [4, 2, 2].iter().zip(&parts).all(|(len, part)| {
+            part.len() == *len && part.chars().all(|c| c.is_ascii_digit())
+        });
+    if valid {
+        Ok(value.to_string())
+    } else {
+        Err(format!("expected a date as YYYY-MM-DD, got '{}'", value))
+    }
+}
+
+/// `--max-file-size` value: a byte count, optionally suffixed with K, M or G (powers of 1024).
+fn parse_size(value: &str) -> Result<u64, String> {
+    let trimmed = value.trim();
+    let (digits, multiplier) = match trimmed.char_indices().last() {
+        Some((i, 'k' | 'K')) => (&trimmed[..i], 1 << 10),
+        Some((i, 'm' | 'M')) => (&trimmed[..i], 1 << 20),
+        Some((i, 'g' | 'G')) => (&trimmed[..i], 1 << 30),
+        _ => (trimmed, 1),
+    };
+    digits
+        .parse::<u64>()
+        .ok()
+        .and_then(|n| n.checked_mul(multiplier))
+        .ok_or_else(|| format!("expected a size such as 500K or 10M, got '{}'", value))
 }
 
 #[derive(Subcommand)]
@@ -29,16 +278,83 @@ enum Commands {
     Scan {
         repo: String,
 
-        #[arg(short, long)]
-        json: bool,
+        #[command(flatten)]
+        output: OutputArgs,
+
+        /// Scan every commit of every branch instead of only the branch tips
+        #[arg(long)]
+        history: bool,
+
+        #[command(flatten)]
+        concurrency: ConcurrencyArgs,
     },
 
     ScanAll {
-        #[arg(short, long)]
-        json: bool,
+        #[command(flatten)]
+        output: OutputArgs,
 
         #[arg(long)]
         private_only: bool,
+
+        #[command(flatten)]
+        filters: RepoFilterArgs,
+
+        #[command(flatten)]
+        concurrency: ConcurrencyArgs,
+    },
+
+    /// Scan every repository of an organization
+    ScanOrg {
+        org: String,
+
+        #[command(flatten)]
+        output: OutputArgs,
+
+        #[command(flatten)]
+        filters: RepoFilterArgs,
+
+        #[command(flatten)]
+        concurrency: ConcurrencyArgs,
+    },
+
+    /// Scan the repositories owned by a user
+    ScanUser {
+        login: String,
+
+        #[command(flatten)]
+        output: OutputArgs,
+
+        #[command(flatten)]
+        filters: RepoFilterArgs,
+
+        #[command(flatten)]
+        concurrency: ConcurrencyArgs,
+    },
+
+    ScanPath {
+        path: PathBuf,
+
+        #[command(flatten)]
+        output: OutputArgs,
+    },
+
+    /// Scan the lines added in `git diff --cached`; exits non-zero on findings
+    ScanStaged {
+        #[arg(default_value = ".")]
+        path: PathBuf,
+
+        #[command(flatten)]
+        output: OutputArgs,
+    },
+
+    /// Install a pre-commit hook that runs scan-staged
+    InstallHook {
+        #[arg(default_value = ".")]
+        path: PathBuf,
+
+        /// Overwrite an existing pre-commit hook
+        #[arg(long)]
+        force: bool,
     },
 
     Patterns,
@@ -49,28 +365,9 @@ async fn main() {
     dotenv().ok();
     let cli = Cli::parse();
 
-    let token = match env::var("GITHUB_TOKEN") {
-        Ok(t) => t,
-        Err(_) => {
-            eprintln!(
-                "{} GITHUB_TOKEN not found. Set it in .env or environment.",
-                "Error:".red().bold()
-            );
-            eprintln!("  export GITHUB_TOKEN=ghp_your_token_here");
-            std::process::exit(1);
-        }
-    };
-
-    let github = match GitHubClient::new(&token) {
-        Ok(c) => c,
-        Err(e) => {
-            eprintln!("{} Failed to create GitHub client: {}", "Error:".red().bold(), e);
-            std::process::exit(1);
-        }
-    };
-
     match cli.command {
         Commands::Repos => {
+            let github = github_client(&cli.github).await;
             println!("{}", " Fetching your repositories...".cyan());
             match github.list_repos().await {
                 Ok(repos) => {
@@ -90,101 +387,179 @@ async fn main() {
                 }
                 Err(e) => {
                     eprintln!("{} Failed to list repos: {}", "Error:".red().bold(), e);
+                    std::process::exit(EXIT_ERROR);
                 }
             }
         }
 
-        Commands::Scan { repo, json } => {
+        Commands::Scan { repo, output, history, concurrency } => {
+            let github = github_client(&cli.github).await;
+            let scanner = load_scanner(&cli.detection);
+            let limiter = concurrency.limiter();
             let (owner, repo_name) = parse_repo(&repo, &github).await;
-            scan_repository(&github, &owner, &repo_name, json).await;
+            let code = if history {
+                scan_history(&github, &scanner, &limiter, &owner, &repo_name, &output, &cli.github).await
+            } else {
+                scan_repository(&github, &scanner, &limiter, &owner, &repo_name, &output, &cli.github).await
+            };
+            std::process::exit(code);
         }
 
-        Commands::ScanAll { json, private_only } => {
-            println!("{}", " Scanning all repositories...".cyan());
-            match github.list_repos().await {
-                Ok(repos) => {
-                    let scanner = Scanner::new();
-                    let mut total_files = 0;
-                    let mut total_findings = 0;
-                    let mut all_findings = Vec::new();
-
-                    let repos_to_scan: Vec<_> = if private_only {
-                        repos.into_iter().filter(|r| r.private).collect()
-                    } else {
-                        repos
-                    };
-
-                    for repo in &repos_to_scan {
-                        let parts: Vec<&str> = repo.full_name.split('/').collect();
-                        if parts.len() == 2 {
-                            let (owner, name) = (parts[0], parts[1]);
-                            
-                            if !json {
-                                println!("\nScanning {}...", repo.full_name.cyan());
-                            }
-
-                            let branches = match github.list_branches(owner, name).await {
-                                Ok(b) => b,
-                                Err(_) => {
-                                    if !json {
-                                        eprintln!("  {} Could not list branches", "!".yellow());
-                                    }
-                                    continue;
-                                }
-                            };
-
-                            if !json {
-                                let branch_names: Vec<&str> = branches.iter().map(|b| b.name.as_str()).collect();
-                                println!("  Branches ({}): {}", branches.len(), branch_names.join(", ").yellow());
-                            }
-
-                            for branch in &branches {
-                                match github.get_repo_files(owner, name, "", Some(&branch.name)).await {
-                                    Ok(files) => {
-                                        total_files += files.len();
-                                        for file_path in files {
-                                            if let Ok(content) = github.get_file_content(owner, name, &file_path, Some(&branch.name)).await {
-                                                let findings = scanner.scan_content(&file_path, &content);
-                                                total_findings += findings.len();
-                                                
-                                                for mut finding in findings {
-                                                    finding.file_path = format!("{}/[{}] {}", repo.full_name, branch.name, finding.file_path);
-                                                    all_findings.push(finding);
-                                                }
-                                            }
-                                        }
-                                    }
-                                    Err(_) => continue,
-                                }
-                            }
-                        }
-                    }
+        Commands::ScanAll { output, private_only, filters, concurrency } => {
+            let github = github_client(&cli.github).await;
+            if !output.quiet() {
+                println!("{}", " Scanning all repositories...".cyan());
+            }
+            let repos = repos_or_exit(github.list_repos().await)
+                .into_iter()
+                .filter(|r| (!private_only || r.private) && filters.matches(r))
+                .collect();
+            let scanner = load_scanner(&cli.detection);
+            let code =
+                scan_repo_list(&github, &scanner, repos, &output, &cli.github, &concurrency, "all repositories").await;
+            std::process::exit(code);
+        }
 
-                    if json {
-                        print_findings_json(&all_findings);
-                    } else {
-                        if !all_findings.is_empty() {
-                            print_findings(&all_findings, "all repositories");
-                        }
-                        print_scan_summary(repos_to_scan.len(), total_files, total_findings);
-                    }
+        Commands::ScanOrg { org, output, filters, concurrency } => {
+            let github = github_client(&cli.github).await;
+            if !output.quiet() {
+                println!("{} {}...", " Scanning organization".cyan(), org.cyan().bold());
+            }
+            let repos = repos_or_exit(github.list_org_repos(&org).await)
+                .into_iter()
+                .filter(|r| filters.matches(r))
+                .collect();
+            let scanner = load_scanner(&cli.detection);
+            let code =
+                scan_repo_list(&github, &scanner, repos, &output, &cli.github, &concurrency, &org).await;
+            std::process::exit(code);
+        }
+
+        Commands::ScanUser { login, output, filters, concurrency } => {
+            let github = github_client(&cli.github).await;
+            if !output.quiet() {
+                println!("{} {}...", " Scanning repositories of".cyan(), login.cyan().bold());
+            }
+            let repos = repos_or_exit(github.list_user_repos(&login).await)
+                .into_iter()
+                .filter(|r| filters.matches(r))
+                .collect();
+            let scanner = load_scanner(&cli.detection);
+            let code =
+                scan_repo_list(&github, &scanner, repos, &output, &cli.github, &concurrency, &login).await;
+            std::process::exit(code);
+        }
+
+        Commands::ScanPath { path, output } => {
+            let scanner = load_scanner(&cli.detection);
+            let display_name = path.display().to_string();
+
+            if !output.quiet() {
+                println!("Scanning {}...", display_name.cyan());
+            }
+
+            match local::scan_path(&path, &scanner) {
+                Ok(result) => {
+                    let code = report(
+                        &output,
+                        &cli.github,
+                        &scanner,
+                        result.findings,
+                        &display_name,
+                        ScanSummary {
+                            repos: 1,
+                            files: result.total_files,
+                            errors: result.errors,
+                            skipped: result.skipped,
+                            ..Default::default()
+                        },
+                    )
+                    .await;
+                    std::process::exit(code);
                 }
                 Err(e) => {
-                    eprintln!("{} Failed to list repos: {}", "Error:".red().bold(), e);
+                    eprintln!("{} Failed to scan {}: {}", "Error:".red().bold(), display_name, e);
+                    std::process::exit(EXIT_ERROR);
                 }
             }
         }
 
+        Commands::ScanStaged { path, output } => {
+            let scanner = load_scanner(&cli.detection);
+
+            let staged = match git::staged_diff(&path) {
+                Ok(d) => d,
+                Err(e) => {
+                    eprintln!("{} Failed to read staged changes: {}", "Error:".red().bold(), e);
+                    std::process::exit(EXIT_ERROR);
+                }
+            };
+
+            let parsed = diff::parse_diff(&staged);
+            let errors = parsed
+                .unreadable
+                .into_iter()
+                .map(|header| ScanError::new(header, ErrorKind::Decode, "cannot read the file path of this diff"))
+                .collect();
+            let files: Vec<_> = parsed
+                .files
+                .into_iter()
+                .filter(|f| paths::is_scannable_file(&f.path) && scanner.in_scope(&f.path))
+                .collect();
+
+            let mut all_findings = Vec::new();
+            for file in &files {
+                let added = diff::added_lines(&file.patch);
+                let lines: Vec<(usize, &str)> = added.iter().map(|(n, l)| (*n, l.as_str())).collect();
+                all_findings.extend(scanner.scan_lines(&file.path, &lines));
+            }
+
+            let code = report(
+                &output,
+                &cli.github,
+                &scanner,
+                all_findings,
+                "staged changes",
+                ScanSummary {
+                    repos: 1,
+                    files: files.len(),
+                    errors,
+                    ..Default::default()
+                },
+            )
+            .await;
+
+            if code == EXIT_FINDINGS && !output.quiet() {
+                eprintln!(
+                    "\n{} Commit blocked: remove the secrets above or unstage them.",
+                    "Error:".red().bold()
+                );
+            }
+            std::process::exit(code);
+        }
+
+        Commands::InstallHook { path, force } => match git::install_hook(&path, force) {
+            Ok(hook) => {
+                println!("{} Installed pre-commit hook at {}", "✓".green().bold(), hook.display());
+            }
+            Err(e) => {
+                eprintln!("{} Failed to install hook: {}", "Error:".red().bold(), e);
+                std::process::exit(EXIT_ERROR);
+            }
+        },
+
         Commands::Patterns => {
-            let scanner = Scanner::new();
+            let scanner = load_scanner(&cli.detection);
             println!("\n{}", "Available Secret Patterns:".cyan().bold());
             
             for (i, pattern) in scanner.patterns().iter().enumerate() {
                 println!(
-                    "{}. {} - {}",
+                    "{}. {} [{}] - {}{}",
                     (i + 1).to_string().white().bold(),
                     pattern.name.yellow(),
-                    pattern.description.dimmed()
+                    pattern.severity.as_str(),
+                    pattern.description.dimmed(),
+                    if pattern.multiline { " (multi-line)".cyan().to_string() } else { String::new() }
                 );
             }
             println!("\n{} patterns available\n", scanner.patterns().len().to_string().green());
@@ -192,6 +567,202 @@ async fn main() {
     }
 }
 
+/// Applies the baseline and `--min-severity`, runs `--verify`, prints the
+/// findings in the requested format and returns the process exit code: `EXIT_FINDINGS` if a
+/// reported finding reaches `--fail-on`, otherwise `EXIT_ERROR` for a partial
+/// scan, otherwise 0.
+async fn report(
+    output: &OutputArgs,
+    github_args: &GitHubArgs,
+    scanner: &Scanner,
+    mut findings: Vec<Finding>,
+    target: &str,
+    mut summary: ScanSummary,
+) -> i32 {
+    let filtered: Vec<Finding> = findings
+        .extract_if(.., |f| matches!(f.suppressed, Some(Suppression::Placeholder(_))))
+        .collect();
+    summary.filtered = filtered.len();
+
+    let total = findings.len();
+    findings.retain(|f| f.suppressed.is_none());
+    summary.suppressed = total - findings.len();
+
+    if let Some(path) = &output.baseline {
+        if output.update_baseline {
+            if let Err(e) = Baseline::from_findings(&findings).save(path) {
+                eprintln!("{} Failed to write baseline: {}", "Error:".red().bold(), e);
+                std::process::exit(EXIT_ERROR);
+            }
+            if !output.quiet() {
+                println!(
+                    "{} Baseline {} updated with {} finding(s)",
+                    "✓".green().bold(),
+                    path.display(),
+                    findings.len()
+                );
+            }
+            return if summary.errors.is_empty() { 0 } else { EXIT_ERROR };
+        }
+
+        let baseline = match Baseline::load(path) {
+            Ok(b) => b,
+            Err(e) => {
+                eprintln!("{} Failed to read baseline: {}", "Error:".red().bold(), e);
+                std::process::exit(EXIT_ERROR);
+            }
+        };
+        let total = findings.len();
+        findings.retain(|f| !baseline.contains(f));
+        summary.baselined = total - findings.len();
+    }
+
+    let total = findings.len();
+    findings.retain(|f| f.severity >= output.min_severity);
+    summary.below_threshold = total - findings.len();
+
+    summary.findings = findings.len();
+    summary.by_severity = [Severity::Critical, Severity::High, Severity::Medium, Severity::Low]
+        .into_iter()
+        .map(|s| (s, findings.iter().filter(|f| f.severity == s).count()))
+        .filter(|(_, count)| *count > 0)
+        .collect();
+
+    if output.verify && !findings.is_empty() {
+        let verifier = SecretVerifier::new(
+            &output.verify_endpoint,
+            &github_args.api_url,
+            github_args.ca_cert.as_deref(),
+        );
+        match verifier {
+            Ok(verifier) => verifier.verify(scanner.patterns(), &mut findings).await,
+            Err(e) => {
+                eprintln!("{} Failed to create verification client: {}", "Error:".red().bold(), e);
+                std::process::exit(EXIT_ERROR);
+            }
+        }
+    }
+
+    let shown_filtered: &[Finding] = if output.show_filtered { &filtered } else { &[] };
+    match output.format() {
+        OutputFormat::Json => {
+            print_findings_json(
+                &[findings.as_slice(), shown_filtered].concat(),
+                &summary.errors,
+                &summary.skipped,
+            )
+        }
+        OutputFormat::Sarif => print_findings_sarif(
+            &[findings.as_slice(), shown_filtered].concat(),
+            scanner.patterns(),
+            &summary.errors,
+            &summary.skipped,
+        ),
+        OutputFormat::Text => {
+            print_findings(&findings, target);
+            print_filtered_findings(shown_filtered);
+            print_scan_errors(&summary.errors);
+            print_scan_summary(&summary);
+        }
+    }
+
+    if findings.iter().any(|f| f.severity >= output.fail_on) {
+        EXIT_FINDINGS
+    } else if !summary.errors.is_empty() {
+        EXIT_ERROR
+    } else {
+        0
+    }
+}
+
+fn load_scanner(detection: &DetectionArgs) -> Scanner {
+    let scanner = match patterns::load_patterns(&detection.rules) {
+        Ok(patterns) => Scanner::new(patterns),
+        Err(e) => {
+            eprintln!("{} Failed to load rules: {}", "Error:".red().bold(), e);
+            std::process::exit(EXIT_ERROR);
+        }
+    };
+
+    let filter = if detection.no_placeholder_filter {
+        None
+    } else {
+        match &detection.stopwords {
+            None => Some(PlaceholderFilter::default()),
+            Some(path) => match PlaceholderFilter::default().with_stopwords_file(path) {
+                Ok(filter) => Some(filter),
+                Err(e) => {
+                    eprintln!("{} Failed to read stopwords {}: {}", "Error:".red().bold(), path.display(), e);
+                    std::process::exit(EXIT_ERROR);
+                }
+            },
+        }
+    };
+    let max_file_size = Some(detection.max_file_size).filter(|&bytes| bytes > 0);
+    let mut scanner = scanner
+        .with_placeholder_filter(filter)
+        .with_max_file_size(max_file_size);
+
+    if let Some(path) = &detection.config {
+        let config = match Config::load(path) {
+            Ok(c) => c,
+            Err(e) => {
+                eprintln!("{} Failed to load config: {}", "Error:".red().bold(), e);
+                std::process::exit(EXIT_ERROR);
+            }
+        };
+        for name in config.rules.keys() {
+            let known = scanner.patterns().iter().any(|p| p.name == *name)
+                || [ENTROPY_BASE64_TYPE, ENTROPY_HEX_TYPE].contains(&name.as_str());
+            if !known {
+                eprintln!("{} Config {} scopes unknown rule '{}'", "Warning:".yellow().bold(), path.display(), name);
+            }
+        }
+        scanner = scanner.with_config(config);
+    }
+
+    if detection.entropy {
+        scanner.with_entropy(EntropyConfig {
+            base64_threshold: detection.entropy_base64,
+            hex_threshold: detection.entropy_hex,
+            min_length: detection.entropy_min_length,
+        })
+    } else {
+        scanner
+    }
+}
+
+async fn github_client(args: &GitHubArgs) -> GitHubClient {
+    let credentials = match (args.app_id, &args.app_key) {
+        (Some(app_id), Some(key_file)) => Credentials::app(app_id, key_file, args.installation_id),
+        _ => match env::var("GITHUB_TOKEN") {
+            Ok(t) => Ok(Credentials::Token(t)),
+            Err(_) => {
+                eprintln!(
+                    "{} GITHUB_TOKEN not found. Set it in .env or environment.",
+                    "Error:".red().bold()
+                );
+                eprintln!("  export GITHUB_TOKEN=ghp_your_token_here");
+                eprintln!("  or authenticate as a GitHub App with --app-id and --app-key");
+                std::process::exit(EXIT_ERROR);
+            }
+        },
+    };
+
+    let client = match credentials {
+        Ok(credentials) => GitHubClient::new(&credentials, &args.api_url, args.ca_cert.as_deref()).await,
+        Err(e) => Err(e),
+    };
+
+    match client {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("{} Failed to create GitHub client: {}", "Error:".red().bold(), e);
+            std::process::exit(EXIT_ERROR);
+        }
+    }
+}
+
 async fn parse_repo(repo: &str, github: &GitHubClient) -> (String, String) {
     if repo.contains('/') {
         let parts: Vec<&str> = repo.split('/').collect();
@@ -204,74 +775,272 @@ async fn parse_repo(repo: &str, github: &GitHubClient) -> (String, String) {
                     (parts[0].to_string(), parts[1].to_string())
                 } else {
                     eprintln!("{} Repository '{}' not found", "Error:".red().bold(), repo);
-                    std::process::exit(1);
+                    std::process::exit(EXIT_ERROR);
                 }
             }
             Err(e) => {
                 eprintln!("{} Failed to resolve repo: {}", "Error:".red().bold(), e);
-                std::process::exit(1);
+                std::process::exit(EXIT_ERROR);
             }
         }
     }
 }
 
-async fn scan_repository(github: &GitHubClient, owner: &str, repo_name: &str, json: bool) {
-    let full_name = format!("{}/{}", owner, repo_name);
-    
-    if !json {
-        println!("Scanning {}...", full_name.cyan());
+fn repos_or_exit(repos: Result<Vec<RepoInfo>, GitHubError>) -> Vec<RepoInfo> {
+    match repos {
+        Ok(r) => r,
+        Err(e) => {
+            eprintln!("{} Failed to list repos: {}", "Error:".red().bold(), e);
+            std::process::exit(EXIT_ERROR);
+        }
     }
+}
+
+/// Scans the branches of several repositories concurrently and reports them together,
+/// prefixing each file path with the repository's full name.
+async fn scan_repo_list(
+    github: &GitHubClient,
+    scanner: &Scanner,
+    repos: Vec<RepoInfo>,
+    output: &OutputArgs,
+    github_args: &GitHubArgs,
+    concurrency: &ConcurrencyArgs,
+    target: &str,
+) -> i32 {
+    let quiet = output.quiet();
+    let limiter = concurrency.limiter();
+
+    if !quiet {
+        println!("Repositories to scan: {}", repos.len().to_string().cyan());
+    }
+
+    let results = join_all(repos.iter().map(|repo| {
+        let limiter = &limiter;
+        async move {
+            let (owner, name) = repo.full_name.split_once('/')?;
+
+            if !quiet {
+                println!("\nScanning {}...", repo.full_name.cyan());
+            }
+
+            let branches = {
+                let _permit = limiter.acquire().await.expect("semaphore closed");
+                github.list_branches(owner, name).await
+            };
+            let branches = match branches {
+                Ok(b) => b,
+                Err(e) => return Some(Err(ScanError::github("branch list", &e))),
+            };
+
+            if !quiet {
+                let branch_names: Vec<&str> = branches.iter().map(|b| b.name.as_str()).collect();
+                println!(
+                    "  {} branches ({}): {}",
+                    repo.full_name,
+                    branches.len(),
+                    branch_names.join(", ").yellow()
+                );
+            }
+
+            Some(Ok(remote::scan_branches(github, scanner, limiter, owner, name, &branches, quiet).await))
+        }
+    }))
+    .await;
 
-    let scanner = Scanner::new();
-    let mut all_findings = Vec::new();
     let mut total_files = 0;
-    let total_branches;
+    let mut errors = Vec::new();
+    let mut skipped = Vec::new();
+    let mut all_findings = Vec::new();
+
+    for (repo, result) in repos.iter().zip(results) {
+        let result = match result {
+            Some(Ok(r)) => r,
+            Some(Err(e)) => {
+                errors.push(ScanError {
+                    repo: Some(repo.full_name.clone()),
+                    ..e
+                });
+                continue;
+            }
+            None => continue,
+        };
+        total_files += result.total_files;
+        errors.extend(result.errors.into_iter().map(|e| ScanError {
+            repo: Some(repo.full_name.clone()),
+            ..e
+        }));
+        skipped.extend(result.skipped.into_iter().map(|file| SkippedFile {
+            path: format!("{}/{}", repo.full_name, file.path),
+            ..file
+        }));
+
+        for mut finding in result.findings {
+            finding.file_path = format!("{}/{}", repo.full_name, finding.file_path);
+            all_findings.push(finding);
+        }
+    }
+
+    report(
+        output,
+        github_args,
+        scanner,
+        all_findings,
+        target,
+        ScanSummary {
+            repos: repos.len(),
+            files: total_files,
+            errors,
+            skipped,
+            rate_limit_wait: github.rate_limit_wait(),
+            ..Default::default()
+        },
+    )
+    .await
+}
+
+async fn scan_repository(
+    github: &GitHubClient,
+    scanner: &Scanner,
+    limiter: &Semaphore,
+    owner: &str,
+    repo_name: &str,
+    output: &OutputArgs,
+    github_args: &GitHubArgs,
+) -> i32 {
+    let full_name = format!("{}/{}", owner, repo_name);
+    let quiet = output.quiet();
+
+    if !quiet {
+        println!("Scanning {}...", full_name.cyan());
+    }
 
     let branches = match github.list_branches(owner, repo_name).await {
         Ok(b) => b,
         Err(e) => {
             eprintln!("{} Failed to list branches: {}", "Error:".red().bold(), e);
-            return;
+            std::process::exit(EXIT_ERROR);
         }
     };
 
-    total_branches = branches.len();
-
-    if !json {
+    if !quiet {
         let branch_names: Vec<&str> = branches.iter().map(|b| b.name.as_str()).collect();
-        println!("Branches ({}): {}", total_branches, branch_names.join(", ").yellow());
+        println!("Branches ({}): {}", branches.len(), branch_names.join(", ").yellow());
     }
 
-    for branch in &branches {
-        if !json {
-            println!("  Scanning branch: {}...", branch.name.cyan());
+    let result = remote::scan_branches(github, scanner, limiter, owner, repo_name, &branches, quiet).await;
+
+    report(
+        output,
+        github_args,
+        scanner,
+        result.findings,
+        &full_name,
+        ScanSummary {
+            repos: 1,
+            branches: branches.len(),
+            files: result.total_files,
+            errors: result.errors,
+            skipped: result.skipped,
+            rate_limit_wait: github.rate_limit_wait(),
+            ..Default::default()
+        },
+    )
+    .await
+}
+
+async fn scan_history(
+    github: &GitHubClient,
+    scanner: &Scanner,
+    limiter: &Semaphore,
+    owner: &str,
+    repo_name: &str,
+    output: &OutputArgs,
+    github_args: &GitHubArgs,
+) -> i32 {
+    let full_name = format!("{}/{}", owner, repo_name);
+    let quiet = output.quiet();
+
+    if !quiet {
+        println!("Scanning history of {}...", full_name.cyan());
+    }
+
+    let branches = match github.list_branches(owner, repo_name).await {
+        Ok(b) => b,
+        Err(e) => {
+            eprintln!("{} Failed to list branches: {}", "Error:".red().bold(), e);
+            std::process::exit(EXIT_ERROR);
         }
+    };
 
-        match github.get_repo_files(owner, repo_name, "", Some(&branch.name)).await {
-            Ok(files) => {
-                total_files += files.len();
-                for file_path in files {
-                    match github.get_file_content(owner, repo_name, &file_path, Some(&branch.name)).await {
-                        Ok(content) => {
-                            let findings = scanner.scan_content(&file_path, &content);
-                            for mut finding in findings {
-                                finding.file_path = format!("[{}] {}", branch.name, finding.file_path);
-                                all_findings.push(finding);
-                            }
-                        }
-                        Err(_) => continue,
-                    }
-                }
+    let listings = join_all(branches.iter().map(|branch| async move {
+        let _permit = limiter.acquire().await.expect("semaphore closed");
+        (branch, github.list_commits(owner, repo_name, &branch.commit_sha).await)
+    }))
+    .await;
+
+    let mut seen = HashSet::new();
+    let mut commits = Vec::new();
+    let mut list_errors = Vec::new();
+    for (branch, listing) in listings {
+        let shas = match listing {
+            Ok(s) => s,
+            Err(e) => {
+                list_errors.push(ScanError::github(format!("branch {}", branch.name), &e));
+                continue;
+            }
+        };
+        for sha in shas {
+            if seen.insert(sha.clone()) {
+                commits.push(sha);
             }
-            Err(_) => continue,
         }
     }
 
-    if json {
-        print_findings_json(&all_findings);
-    } else {
-        print_findings(&all_findings, &full_name);
-        println!("Branches scanned: {}", total_branches.to_string().cyan());
-        print_scan_summary(1, total_files, all_findings.len());
+    if !quiet {
+        println!("Commits ({}) across {} branch(es)", commits.len().to_string().cyan(), branches.len());
     }
-}
\ No newline at end of file
+
+    let result = remote::scan_commits(github, scanner, limiter, owner, repo_name, &commits).await;
+
+    report(
+        output,
+        github_args,
+        scanner,
+        result.findings,
+        &full_name,
+        ScanSummary {
+            repos: 1,
+            branches: branches.len(),
+            commits: commits.len(),
+            files: result.total_files,
+            errors: [list_errors, result.errors].concat(),
+            skipped: result.skipped,
+            rate_limit_wait: github.rate_limit_wait(),
+            ..Default::default()
+        },
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_size_accepts_bytes_and_suffixes() {
+        assert_eq!(parse_size("0"), Ok(0));
+        assert_eq!(parse_size("1500"), Ok(1500));
+        assert_eq!(parse_size("500K"), Ok(500 * 1024));
+        assert_eq!(parse_size("10m"), Ok(10 * 1024 * 1024));
+        assert_eq!(parse_size(" 2G "), Ok(2 * 1024 * 1024 * 1024));
+    }
+
+    #[test]
+    fn parse_size_rejects_invalid_values() {
+        assert!(parse_size("").is_err());
+        assert!(parse_size("M").is_err());
+        assert!(parse_size("10MB").is_err());
+        assert!(parse_size("-1").is_err());
+        assert!(parse_size("18446744073709551615G").is_err());
+    }
+}
@@ -0,0 +1,70 @@
+pub fn is_scannable_file(path: &str) -> bool {
+    let scannable_extensions = [
+        ".rs", ".py", ".js", ".ts", ".jsx", ".tsx", ".go", ".java", ".rb", ".php",
+        ".cs", ".cpp", ".c", ".h", ".hpp", ".swift", ".kt", ".scala", ".sh", ".bash",
+        ".env", ".yml", ".yaml", ".json", ".toml", ".xml", ".ini", ".cfg", ".conf",
+        ".properties", ".md", ".txt", ".sql", ".dockerfile", ".tf", ".tfvars", ".pem", ".key",
+    ];
+
+    let path_lower = path.to_lowercase();
+
+    let secret_filenames = [
+        ".env", ".env.local", ".env.development", ".env.production",
+        "credentials", "secrets", "config", ".npmrc", ".pypirc",
+    ];
+
+    for filename in secret_filenames {
+        if path_lower.ends_with(filename) {
+            return true;
+        }
+    }
+
+    scannable_extensions.iter().any(|ext| path_lower.ends_with(ext))
+}
+
+pub fn should_skip_dir(path: &str) -> bool {
+    let skip_dirs = [
+        "node_modules", ".git", "vendor", "target", "dist", "build",
+        "__pycache__", ".venv", "venv", ".idea", ".vscode", "coverage",
+        ".next", ".nuxt", "out", "bin", "obj", "packages",
+    ];
+
+    // Whole segments only: `about/` or `cabinet/` must not match "out" or "bin".
+    path.split(['/', '\\'])
+        .any(|segment| skip_dirs.contains(&segment))
+}
+
+/// Whether a repository file lives under a skipped directory, judged on its parent dir only.
+pub fn in_skipped_dir(file_path: &str) -> bool {
+    file_path
+        .rsplit_once('/')
+        .is_some_and(|(dir, _)| should_skip_dir(dir))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn skip_dirs_match_whole_segments() {
+        assert!(!should_skip_dir("about/x"));
+        assert!(!should_skip_dir("cabinet/x"));
+        assert!(should_skip_dir("src/bin/x"));
+        assert!(should_skip_dir("a/node_modules/b"));
+        assert!(should_skip_dir("node_modules"));
+    }
+
+    #[test]
+    fn skip_dirs_accept_backslashes() {
+        assert!(should_skip_dir("a\\node_modules\\b"));
+        assert!(should_skip_dir("src\\bin"));
+        assert!(!should_skip_dir("about\\cabinet"));
+    }
+
+    #[test]
+    fn skipped_dir_is_judged_on_the_parent_only() {
+        assert!(in_skipped_dir("vendor/lib/key.pem"));
+        assert!(!in_skipped_dir("build"));
+        assert!(!in_skipped_dir("src/build.rs"));
+    }
+}
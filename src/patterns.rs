@@ -1,133 +1,203 @@
+use clap::ValueEnum;
 use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::validators::Validator;
+use crate::verify::Service;
+
+const BUNDLED_RULES: &str = include_str!("../regex.json");
+
+/// How bad a leak of this secret would be. Ordered from least to most severe.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Low,
+    #[default]
+    Medium,
+    High,
+    Critical,
+}
+
+impl Severity {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Severity::Low => "low",
+            Severity::Medium => "medium",
+            Severity::High => "high",
+            Severity::Critical => "critical",
+        }
+    }
+}
+
+/// How likely a match is to be a real secret rather than a false positive.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Confidence {
+    Low,
+    #[default]
+    Medium,
+    High,
+}
+
+impl Confidence {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Confidence::Low => "low",
+            Confidence::Medium => "medium",
+            Confidence::High => "high",
+        }
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct SecretPattern {
     pub name: String,
     pub pattern: Regex,
     pub description: String,
+    /// Matched against the whole content instead of line by line.
+    pub multiline: bool,
+    pub severity: Severity,
+    pub confidence: Confidence,
+    pub validator: Option<Validator>,
+    /// Service queried by `--verify` to tell whether a match is still live.
+    pub verifier: Option<Service>,
 }
 
 impl SecretPattern {
-    pub fn new(name: &str, pattern: &str, description: &str) -> Self {
-        Self {
+    pub fn new(name: &str, pattern: &str, description: &str) -> Result<Self, regex::Error> {
+        Ok(Self {
             name: name.to_string(),
-            pattern: Regex::new(pattern).expect("Invalid regex pattern"),
+            pattern: Regex::new(pattern)?,
             description: description.to_string(),
+            multiline: false,
+            severity: Severity::default(),
+            confidence: Confidence::default(),
+            validator: None,
+            verifier: None,
+        })
+    }
+}
+
+#[derive(Debug)]
+pub enum RuleError {
+    Io { source: String, error: std::io::Error },
+    Parse { source: String, error: serde_json::Error },
+    MissingRegex { source: String, name: String },
+    InvalidRegex { source: String, name: String, error: regex::Error },
+}
+
+impl fmt::Display for RuleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RuleError::Io { source, error } => write!(f, "cannot read rule file {}: {}", source, error),
+            RuleError::Parse { source, error } => write!(f, "invalid rule file {}: {}", source, error),
+            RuleError::MissingRegex { source, name } => {
+                write!(f, "rule '{}' in {} has no regex", name, source)
+            }
+            RuleError::InvalidRegex { source, name, error } => {
+                write!(f, "rule '{}' in {} has an invalid regex:\n{}", name, source, error)
+            }
         }
     }
 }
 
-pub fn get_default_patterns() -> Vec<SecretPattern> {
-    vec![
-        SecretPattern::new(
-            "AWS Access Key ID",
-            r"AKIA[0-9A-Z]{16}",
-            "Amazon Web Services access key",
-        ),
-        SecretPattern::new(
-            "AWS Secret Key",
-            r#"(?i)aws(.{0,20})?['"][0-9a-zA-Z/+]{40}['"]"#,
-            "Amazon Web Services secret key",
-        ),
-        SecretPattern::new(
-            "GitHub Token",
-            r"gh[pousr]_[A-Za-z0-9_]{36,255}",
-            "GitHub Personal Access Token",
-        ),
-        SecretPattern::new(
-            "GitHub OAuth",
-            r"gho_[A-Za-z0-9_]{36,255}",
-            "GitHub OAuth Access Token",
-        ),
-        SecretPattern::new(
-            "Private Key",
-            r"-----BEGIN\s+(RSA|EC|DSA|OPENSSH|PGP)?\s*PRIVATE KEY-----",
-            "Private key file",
-        ),
-        SecretPattern::new(
-            "Generic API Key",
-            r#"(?i)(api[_-]?key|apikey)\s*[:=]\s*['"]?[a-zA-Z0-9_\-]{20,}['"]?"#,
-            "Generic API key pattern",
-        ),
-        SecretPattern::new(
-            "JWT Token",
-            r"eyJ[A-Za-z0-9-_]+\.eyJ[A-Za-z0-9-_]+\.[A-Za-z0-9-_]+",
-            "JSON Web Token",
-        ),
-        SecretPattern::new(
-            "Slack Token",
-            r"xox[baprs]-[0-9]{10,13}-[0-9]{10,13}[a-zA-Z0-9-]*",
-            "Slack API Token",
-        ),
-        SecretPattern::new(
-            "Slack Webhook",
-            r"https://hooks\.slack\.com/services/T[A-Z0-9]+/B[A-Z0-9]+/[a-zA-Z0-9]+",
-            "Slack Webhook URL",
-        ),
-        SecretPattern::new(
-            "Google API Key",
-            r"AIza[0-9A-Za-z\-_]{35}",
-            "Google API Key",
-        ),
-        SecretPattern::new(
-            "Stripe Secret Key",
-            r"sk_live_[0-9a-zA-Z]{24,}",
-            "Stripe Secret API Key",
-        ),
-        SecretPattern::new(
-            "Stripe Publishable Key",
-            r"pk_live_[0-9a-zA-Z]{24,}",
-            "Stripe Publishable API Key",
-        ),
-        SecretPattern::new(
-            "Discord Token",
-            r"[MN][A-Za-z\d]{23,}\.[\w-]{6}\.[\w-]{27}",
-            "Discord Bot Token",
-        ),
-        SecretPattern::new(
-            "Password in URL",
-            r"[a-zA-Z]{3,10}://[^/\s:@]{1,100}:[^/\s:@]{1,100}@[^\s/]+",
-            "Password embedded in URL",
-        ),
-        SecretPattern::new(
-            "Generic Password",
-            r#"(?i)(password|passwd|pwd)\s*[:=]\s*['"][^'"]{8,}['"]"#,
-            "Hardcoded password",
-        ),
-        SecretPattern::new(
-            "Heroku API Key",
-            r"[h|H][e|E][r|R][o|O][k|K][u|U].{0,30}[0-9A-F]{8}-[0-9A-F]{4}-[0-9A-F]{4}-[0-9A-F]{4}-[0-9A-F]{12}",
-            "Heroku API Key",
-        ),
-        SecretPattern::new(
-            "SendGrid API Key",
-            r"SG\.[a-zA-Z0-9_-]{22}\.[a-zA-Z0-9_-]{43}",
-            "SendGrid API Key",
-        ),
-        SecretPattern::new(
-            "Twilio API Key",
-            r"SK[a-f0-9]{32}",
-            "Twilio API Key",
-        ),
-        SecretPattern::new(
-            "npm Token",
-            r"npm_[A-Za-z0-9]{36}",
-            "npm Access Token",
-        ),
-        SecretPattern::new(
-            "Vite Token",
-            r"vite_[a-zA-Z0-9]{32,}",
-            "Vite API Token",
-        ),
-        SecretPattern::new(
-            "Supabase Anon Key",
-            r"eyJhbGciOiJIUzI1NiIsInR5cCI6IkpXVCJ9\.[a-zA-Z0-9_-]+\.[a-zA-Z0-9_-]+",
-            "Supabase Anonymous/Public Key (JWT)",
-        ),
-        SecretPattern::new(
-            "Supabase Service Key",
-            r"sbp_[a-f0-9]{40}",
-            "Supabase Service Role Key",
-        ),
-    ]
+impl std::error::Error for RuleError {}
+
+#[derive(Deserialize)]
+struct RuleFile {
+    #[serde(default)]
+    patterns: Vec<RuleDefinition>,
+}
+
+#[derive(Deserialize)]
+struct RuleDefinition {
+    name: String,
+    regex: Option<String>,
+    #[serde(default)]
+    description: String,
+    #[serde(default = "default_enabled")]
+    enabled: bool,
+    #[serde(default)]
+    multiline: bool,
+    #[serde(default)]
+    severity: Severity,
+    #[serde(default)]
+    confidence: Confidence,
+    #[serde(default)]
+    validator: Option<Validator>,
+    #[serde(default)]
+    verifier: Option<Service>,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// Loads the `regex.json` rules built into the binary, then applies each user rule
+/// file in order; `--rules` is how rules change without a rebuild.
+/// A rule whose name already exists replaces it, and `"enabled": false` removes it.
+pub fn load_patterns(rule_files: &[PathBuf]) -> Result<Vec<SecretPattern>, RuleError> {
+    let mut patterns = Vec::new();
+    apply_rules(&mut patterns, "regex.json (bundled)", BUNDLED_RULES)?;
+
+    for path in rule_files {
+        let source = path.display().to_string();
+        let content = read_rule_file(path)?;
+        apply_rules(&mut patterns, &source, &content)?;
+    }
+
+    Ok(patterns)
+}
+
+fn read_rule_file(path: &Path) -> Result<String, RuleError> {
+    fs::read_to_string(path).map_err(|error| RuleError::Io {
+        source: path.display().to_string(),
+        error,
+    })
+}
+
+fn apply_rules(patterns: &mut Vec<SecretPattern>, source: &str, content: &str) -> Result<(), RuleError> {
+    let file: RuleFile = serde_json::from_str(content).map_err(|error| RuleError::Parse {
+        source: source.to_string(),
+        error,
+    })?;
+
+    for rule in file.patterns {
+        let existing = patterns.iter().position(|p| p.name == rule.name);
+
+        if !rule.enabled {
+            if let Some(index) = existing {
+                patterns.remove(index);
+            }
+            continue;
+        }
+
+        let regex = rule.regex.as_deref().ok_or_else(|| RuleError::MissingRegex {
+            source: source.to_string(),
+            name: rule.name.clone(),
+        })?;
+
+        let mut pattern = SecretPattern::new(&rule.name, regex, &rule.description).map_err(|error| {
+            RuleError::InvalidRegex {
+                source: source.to_string(),
+                name: rule.name.clone(),
+                error,
+            }
+        })?;
+        pattern.multiline = rule.multiline;
+        pattern.severity = rule.severity;
+        pattern.confidence = rule.confidence;
+        pattern.validator = rule.validator;
+        pattern.verifier = rule.verifier;
+
+        match existing {
+            Some(index) => patterns[index] = pattern,
+            None => patterns.push(pattern),
+        }
+    }
+
+    Ok(())
 }
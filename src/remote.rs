@@ -0,0 +1,244 @@
+use colored::*;
+use futures::future::join_all;
+use std::collections::HashMap;
+use tokio::sync::Semaphore;
+
+use crate::diff;
+use crate::error::{ErrorKind, ScanError};
+use crate::github::{BlobContent, BranchInfo, GitHubClient};
+use crate::paths::{in_skipped_dir, is_scannable_file};
+use crate::scanner::{Finding, Scanner, SkippedFile};
+
+pub struct RepoScan {
+    pub findings: Vec<Finding>,
+    pub total_files: usize,
+    /// Trees, blobs or commits that could not be fetched; any means a partial scan.
+    pub errors: Vec<ScanError>,
+    /// Files over the size limit, not fetched.
+    pub skipped: Vec<SkippedFile>,
+}
+
+struct BlobLocations {
+    sha: String,
+    size: u64,
+    // path -> branches where this blob lives at that path
+    paths: Vec<(String, Vec<String>)>,
+}
+
+/// Scans the files of every branch, fetching and scanning each unique blob only once.
+/// Findings carry the list of branches where the blob appears at that path.
+/// Every GitHub request holds a permit from `limiter`, which bounds concurrency.
+pub async fn scan_branches(
+    github: &GitHubClient,
+    scanner: &Scanner,
+    limiter: &Semaphore,
+    owner: &str,
+    repo: &str,
+    branches: &[BranchInfo],
+    quiet: bool,
+) -> RepoScan {
+    let mut blobs: Vec<BlobLocations> = Vec::new();
+    let mut blob_index: HashMap<String, usize> = HashMap::new();
+
+    let listings = join_all(branches.iter().map(|branch| async move {
+        let _permit = limiter.acquire().await.expect("semaphore closed");
+        if !quiet {
+            println!("  Listing branch: {}...", branch.name.cyan());
+        }
+        (branch, github.get_repo_files(owner, repo, &branch.commit_sha).await)
+    }))
+    .await;
+
+    let mut errors = Vec::new();
+    for (branch, listing) in listings {
+        let files = match listing {
+            Ok(f) => f,
+            Err(e) => {
+                errors.push(ScanError::github(format!("branch {}", branch.name), &e));
+                continue;
+            }
+        };
+
+        for file in files.into_iter().filter(|f| scanner.in_scope(&f.path)) {
+            let index = *blob_index.entry(file.sha.clone()).or_insert_with(|| {
+                blobs.push(BlobLocations {
+                    sha: file.sha.clone(),
+                    size: file.size,
+                    paths: Vec::new(),
+                });
+                blobs.len() - 1
+            });
+
+            let locations = &mut blobs[index].paths;
+            match locations.iter_mut().find(|(path, _)| *path == file.path) {
+                Some((_, branch_names)) => branch_names.push(branch.name.clone()),
+                None => locations.push((file.path, vec![branch.name.clone()])),
+            }
+        }
+    }
+
+    let mut skipped = Vec::new();
+    for blob in blobs.extract_if(.., |blob| scanner.too_large(blob.size)) {
+        skipped.extend(blob.paths.into_iter().map(|(path, _)| SkippedFile { path, size: blob.size }));
+    }
+
+    if !quiet {
+        println!("  Unique files to scan: {}", blobs.len().to_string().cyan());
+    }
+
+    let results = join_all(blobs.iter().map(|blob| async move {
+        let content = {
+            let _permit = limiter.acquire().await.expect("semaphore closed");
+            github.get_blob_content(owner, repo, &blob.sha).await
+        };
+
+        let content = match content {
+            Ok(c) => c,
+            Err(e) => return Err(ScanError::github(blob.paths[0].0.clone(), &e)),
+        };
+
+        let mut findings = Vec::new();
+        for (path, branch_names) in &blob.paths {
+            for mut finding in scanner.scan_content(path, &content) {
+                finding.url = Some(github.blob_url(owner, repo, &branch_names[0], path, finding.line_number));
+                finding.branches = branch_names.clone();
+                findings.push(finding);
+            }
+        }
+        Ok(findings)
+    }))
+    .await;
+
+    let mut findings = Vec::new();
+    let mut total_files = 0;
+    for result in results {
+        match result {
+            Ok(blob_findings) => {
+                findings.extend(blob_findings);
+                total_files += 1;
+            }
+            Err(e) => errors.push(e),
+        }
+    }
+
+    RepoScan {
+        findings,
+        total_files,
+        errors,
+        skipped,
+    }
+}
+
+/// Scans the lines added by each commit, tagging findings with the commit metadata.
+/// Files whose diff GitHub leaves out are scanned whole, as of that commit.
+pub async fn scan_commits(
+    github: &GitHubClient,
+    scanner: &Scanner,
+    limiter: &Semaphore,
+    owner: &str,
+    repo: &str,
+    commits: &[String],
+) -> RepoScan {
+    let results = join_all(commits.iter().map(|sha| async move {
+        let commit = {
+            let _permit = limiter.acquire().await.expect("semaphore closed");
+            github.get_commit(owner, repo, sha).await
+        };
+
+        let commit = match commit {
+            Ok(c) => c,
+            Err(e) => return Err(ScanError::github(format!("commit {}", sha), &e)),
+        };
+
+        let mut scan = RepoScan {
+            findings: Vec::new(),
+            total_files: 0,
+            errors: Vec::new(),
+            skipped: Vec::new(),
+        };
+
+        if commit.truncated {
+            scan.errors.push(ScanError::new(
+                format!("commit {}", sha),
+                ErrorKind::TooLarge,
+                "the commit changes more files than the GitHub API lists; the rest were not scanned",
+            ));
+        }
+
+        for file in commit
+            .files
+            .iter()
+            .filter(|f| is_scannable_file(&f.path) && !in_skipped_dir(&f.path) && scanner.in_scope(&f.path))
+        {
+            scan.total_files += 1;
+            let added = diff::added_lines(&file.patch);
+            let lines: Vec<(usize, &str)> = added.iter().map(|(n, l)| (*n, l.as_str())).collect();
+
+            for mut finding in scanner.scan_lines(&file.path, &lines) {
+                finding.url = Some(github.blob_url(owner, repo, &commit.info.sha, &file.path, finding.line_number));
+                finding.commit = Some(commit.info.clone());
+                scan.findings.push(finding);
+            }
+        }
+
+        for file in commit
+            .unpatched
+            .iter()
+            .filter(|f| is_scannable_file(&f.path) && !in_skipped_dir(&f.path) && scanner.in_scope(&f.path))
+        {
+            let content = {
+                let _permit = limiter.acquire().await.expect("semaphore closed");
+                github
+                    .get_blob_content_within(owner, repo, &file.sha, |size| scanner.too_large(size))
+                    .await
+            };
+
+            let content = match content {
+                Ok(BlobContent::Text(c)) => c,
+                Ok(BlobContent::TooLarge { size }) => {
+                    scan.skipped.push(SkippedFile {
+                        path: file.path.clone(),
+                        size,
+                    });
+                    continue;
+                }
+                Err(e) => {
+                    scan.errors.push(ScanError::github(format!("{} at commit {}", file.path, sha), &e));
+                    continue;
+                }
+            };
+
+            scan.total_files += 1;
+            for mut finding in scanner.scan_content(&file.path, &content) {
+                finding.url = Some(github.blob_url(owner, repo, &commit.info.sha, &file.path, finding.line_number));
+                finding.commit = Some(commit.info.clone());
+                scan.findings.push(finding);
+            }
+        }
+        Ok(scan)
+    }))
+    .await;
+
+    let mut findings = Vec::new();
+    let mut total_files = 0;
+    let mut errors = Vec::new();
+    let mut skipped = Vec::new();
+    for result in results {
+        match result {
+            Ok(scan) => {
+                findings.extend(scan.findings);
+                total_files += scan.total_files;
+                errors.extend(scan.errors);
+                skipped.extend(scan.skipped);
+            }
+            Err(e) => errors.push(e),
+        }
+    }
+
+    RepoScan {
+        findings,
+        total_files,
+        errors,
+        skipped,
+    }
+}
@@ -1,5 +1,11 @@
-use crate::scanner::Finding;
+use crate::error::ScanError;
+use crate::patterns::{SecretPattern, Severity};
+use crate::scanner::{Finding, SkippedFile, Suppression, Validation};
+use crate::verify::Verified;
 use colored::*;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::time::Duration;
 
 pub fn print_findings(findings: &[Finding], repo_name: &str) {
     if findings.is_empty() {
@@ -13,40 +19,338 @@ pub fn print_findings(findings: &[Finding], repo_name: &str) {
         repo_name.cyan()
     );
 
-    for (i, finding) in findings.iter().enumerate() {
+    // Most severe first; the sort is stable so file order is kept within a level.
+    let mut sorted: Vec<&Finding> = findings.iter().collect();
+    sorted.sort_by_key(|f| std::cmp::Reverse(f.severity));
+
+    for (i, finding) in sorted.into_iter().enumerate() {
         println!(
-            "{}. {} [{}]",
+            "{}. [{}] {} [{}] {}",
             (i + 1).to_string().white().bold(),
-            finding.secret_type.red().bold(),
-            finding.file_path.blue()
-        );
-        println!(
-            "   Line {}: {}",
-            finding.line_number.to_string().yellow(),
-            truncate_line(&finding.line_content, 80).dimmed()
+            severity_label(finding.severity),
+            severity_color(finding.severity, &finding.secret_type),
+            finding.file_path.blue(),
+            format!(
+                "(confidence: {}{})",
+                finding.confidence.as_str(),
+                match finding.validation {
+                    Some(Validation::Valid) => ", validated",
+                    Some(Validation::Invalid) => ", failed validation",
+                    None => "",
+                }
+            )
+            .dimmed()
         );
+        if finding.end_line_number > finding.line_number {
+            println!(
+                "   Lines {}-{}: {}",
+                finding.line_number.to_string().yellow(),
+                finding.end_line_number.to_string().yellow(),
+                highlight_match(finding, 80)
+            );
+        } else {
+            println!(
+                "   Line {}, col {}: {}",
+                finding.line_number.to_string().yellow(),
+                finding.start_column.to_string().yellow(),
+                highlight_match(finding, 80)
+            );
+        }
         println!(
             "   Match: {}",
             finding.masked_text().red()
         );
+        if let Some(verified) = finding.verified {
+            let label = verified.as_str().to_uppercase();
+            let label = match verified {
+                Verified::Active => label.red().bold(),
+                Verified::Inactive => label.green(),
+                Verified::Unknown => label.dimmed(),
+            };
+            println!("   Verified: {}", label);
+        }
+        if !finding.branches.is_empty() {
+            println!("   Branches: {}", finding.branches.join(", ").yellow());
+        }
+        if let Some(commit) = &finding.commit {
+            println!(
+                "   Commit: {} by {} on {}",
+                commit.sha.chars().take(7).collect::<String>().yellow(),
+                commit.author,
+                commit.date
+            );
+        }
+        if let Some(url) = &finding.url {
+            println!("   Link: {}", url.blue().underline());
+        }
         println!();
     }
 }
 
-pub fn print_findings_json(findings: &[Finding]) {
-    let json = serde_json::to_string_pretty(findings).unwrap_or_else(|_| "[]".to_string());
-    println!("{}", json);
+/// Lists the matches dropped by the placeholder filter (`--show-filtered`).
+pub fn print_filtered_findings(filtered: &[Finding]) {
+    if filtered.is_empty() {
+        return;
+    }
+
+    println!("{}", "Filtered as placeholders:".dimmed().bold());
+    for finding in filtered {
+        let reason = match finding.suppressed {
+            Some(Suppression::Placeholder(reason)) => reason.as_str(),
+            _ => "",
+        };
+        println!(
+            "   {} [{}:{}] {} - {}",
+            finding.secret_type,
+            finding.file_path.blue(),
+            finding.line_number,
+            finding.masked_text().dimmed(),
+            reason.yellow()
+        );
+    }
+    println!();
 }
 
-pub fn print_scan_summary(total_repos: usize, total_files: usize, total_findings: usize) {
+/// Lists what could not be scanned, so a partial scan never looks clean.
+pub fn print_scan_errors(errors: &[ScanError]) {
+    if errors.is_empty() {
+        return;
+    }
+
+    println!("{}", "Not scanned:".red().bold());
+    for error in errors {
+        let target = match &error.repo {
+            Some(repo) => format!("{}: {}", repo, error.target),
+            None => error.target.clone(),
+        };
+        println!("   ! [{}] {}: {}", error.kind.as_str().yellow(), target.blue(), error.message);
+    }
+    println!();
+}
+
+#[derive(Serialize)]
+struct JsonReport<'a> {
+    findings: &'a [Finding],
+    errors: &'a [ScanError],
+    skipped: &'a [SkippedFile],
+}
+
+pub fn print_findings_json(findings: &[Finding], errors: &[ScanError], skipped: &[SkippedFile]) {
+    let report = JsonReport {
+        findings,
+        errors,
+        skipped,
+    };
+    println!("{}", serde_json::to_string_pretty(&report).unwrap_or_else(|_| "{}".to_string()));
+}
+
+/// Prints a SARIF 2.1.0 log with one rule per pattern, for code-scanning dashboards.
+/// Scan errors and skipped files become tool execution notifications.
+pub fn print_findings_sarif(
+    findings: &[Finding],
+    patterns: &[SecretPattern],
+    errors: &[ScanError],
+    skipped: &[SkippedFile],
+) {
+    let mut rules: Vec<(String, String)> = patterns
+        .iter()
+        .map(|p| (p.name.clone(), p.description.clone()))
+        .collect();
+
+    // Findings can come from rules that are not regex patterns.
+    for finding in findings {
+        if !rules.iter().any(|(name, _)| *name == finding.secret_type) {
+            rules.push((finding.secret_type.clone(), finding.secret_type.clone()));
+        }
+    }
+
+    let sarif_rules: Vec<Value> = rules
+        .iter()
+        .map(|(name, description)| {
+            let severity = patterns
+                .iter()
+                .find(|p| p.name == *name)
+                .map(|p| p.severity)
+                .or_else(|| findings.iter().find(|f| f.secret_type == *name).map(|f| f.severity))
+                .unwrap_or_default();
+            json!({
+                "id": rule_id(name),
+                "name": name,
+                "shortDescription": { "text": description },
+                "defaultConfiguration": { "level": sarif_level(severity) },
+            })
+        })
+        .collect();
+
+    let results: Vec<Value> = findings
+        .iter()
+        .map(|finding| {
+            let rule_index = rules
+                .iter()
+                .position(|(name, _)| *name == finding.secret_type)
+                .unwrap_or(0);
+            let mut result = json!({
+                "ruleId": rule_id(&finding.secret_type),
+                "ruleIndex": rule_index,
+                "level": sarif_level(finding.severity),
+                "message": {
+                    "text": format!("{} detected: {}", finding.secret_type, finding.masked_text()),
+                },
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": { "uri": finding.file_path },
+                        "region": {
+                            "startLine": finding.line_number,
+                            "startColumn": finding.start_column,
+                            "endLine": finding.end_line_number,
+                            "endColumn": finding.end_column + 1,
+                        },
+                    },
+                }],
+                "partialFingerprints": {
+                    "secretHash/v1": finding.secret_hash(),
+                },
+                "properties": {
+                    "severity": finding.severity.as_str(),
+                    "confidence": finding.confidence.as_str(),
+                    "validation": finding.validation,
+                    "verified": finding.verified,
+                },
+            });
+            if let Some(Suppression::Placeholder(reason)) = finding.suppressed {
+                result["suppressions"] = json!([{
+                    "kind": "external",
+                    "justification": format!("Placeholder filter: {}", reason.as_str()),
+                }]);
+            }
+            result
+        })
+        .collect();
+
+    let notifications: Vec<Value> = errors
+        .iter()
+        .map(|error| {
+            let uri = match &error.repo {
+                Some(repo) => format!("{}/{}", repo, error.target),
+                None => error.target.clone(),
+            };
+            json!({
+                "level": "error",
+                "message": { "text": error.message },
+                "locations": [{
+                    "physicalLocation": { "artifactLocation": { "uri": uri } },
+                }],
+                "properties": { "kind": error.kind },
+            })
+        })
+        .chain(skipped.iter().map(|file| {
+            json!({
+                "level": "note",
+                "message": { "text": format!("skipped: {} exceeds --max-file-size", format_size(file.size)) },
+                "locations": [{
+                    "physicalLocation": { "artifactLocation": { "uri": file.path } },
+                }],
+                "properties": { "size": file.size },
+            })
+        }))
+        .collect();
+
+    let log = json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "mini-guardian",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": "https://github.com/4D4J/mini-guardian",
+                    "rules": sarif_rules,
+                },
+            },
+            "results": results,
+            "invocations": [{
+                "executionSuccessful": errors.is_empty(),
+                "toolExecutionNotifications": notifications,
+            }],
+        }],
+    });
+
+    println!("{}", serde_json::to_string_pretty(&log).unwrap_or_else(|_| "{}".to_string()));
+}
+
+fn sarif_level(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Critical | Severity::High => "error",
+        Severity::Medium => "warning",
+        Severity::Low => "note",
+    }
+}
+
+fn severity_label(severity: Severity) -> ColoredString {
+    severity_color(severity, &severity.as_str().to_uppercase())
+}
+
+fn severity_color(severity: Severity, text: &str) -> ColoredString {
+    match severity {
+        Severity::Critical => text.red().bold().reversed(),
+        Severity::High => text.red().bold(),
+        Severity::Medium => text.yellow().bold(),
+        Severity::Low => text.blue().bold(),
+    }
+}
+
+fn rule_id(name: &str) -> String {
+    let slug: Vec<String> = name
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .map(|part| part.to_lowercase())
+        .collect();
+    slug.join("-")
+}
+
+#[derive(Debug, Default)]
+pub struct ScanSummary {
+    pub repos: usize,
+    pub branches: usize,
+    pub commits: usize,
+    pub files: usize,
+    pub findings: usize,
+    /// Reported findings per severity, most severe first.
+    pub by_severity: Vec<(Severity, usize)>,
+    /// Findings hidden by `--min-severity`.
+    pub below_threshold: usize,
+    pub baselined: usize,
+    pub suppressed: usize,
+    /// Matches dropped by the placeholder filter.
+    pub filtered: usize,
+    /// Files, trees or commits that could not be scanned.
+    pub errors: Vec<ScanError>,
+    /// Files over `--max-file-size`.
+    pub skipped: Vec<SkippedFile>,
+    pub rate_limit_wait: Duration,
+}
+
+pub fn print_scan_summary(summary: &ScanSummary) {
     println!("{}", "SCAN SUMMARY".white().bold());
-    println!("  Repositories scanned: {}", total_repos.to_string().cyan());
-    println!("  Files scanned:        {}", total_files.to_string().cyan());
+    println!("  Repositories scanned: {}", summary.repos.to_string().cyan());
+    if summary.branches > 0 {
+        println!("  Branches scanned:     {}", summary.branches.to_string().cyan());
+    }
+    if summary.commits > 0 {
+        println!("  Commits scanned:      {}", summary.commits.to_string().cyan());
+    }
+    println!("  Files scanned:        {}", summary.files.to_string().cyan());
     
-    if total_findings > 0 {
+    if summary.findings > 0 {
+        let breakdown: Vec<String> = summary
+            .by_severity
+            .iter()
+            .map(|(severity, count)| format!("{} {}", count, severity_color(*severity, severity.as_str())))
+            .collect();
         println!(
-            "  Secrets found:        {}",
-            total_findings.to_string().red().bold()
+            "  Secrets found:        {} ({})",
+            summary.findings.to_string().red().bold(),
+            breakdown.join(", ")
         );
     } else {
         println!(
@@ -54,13 +358,112 @@ pub fn print_scan_summary(total_repos: usize, total_files: usize, total_findings
             "0".green().bold()
         );
     }
+
+    if summary.suppressed > 0 {
+        println!(
+            "  Suppressed (inline):  {}",
+            summary.suppressed.to_string().dimmed()
+        );
+    }
+
+    if summary.filtered > 0 {
+        println!(
+            "  Placeholders:         {}",
+            summary.filtered.to_string().dimmed()
+        );
+    }
+
+    if summary.below_threshold > 0 {
+        println!(
+            "  Below min severity:   {}",
+            summary.below_threshold.to_string().dimmed()
+        );
+    }
+
+    if summary.baselined > 0 {
+        println!(
+            "  Known (baseline):     {}",
+            summary.baselined.to_string().dimmed()
+        );
+    }
+
+    if !summary.errors.is_empty() {
+        println!(
+            "  Errors:               {} {}",
+            summary.errors.len().to_string().red().bold(),
+            "(partial scan)".yellow()
+        );
+    }
+
+    if !summary.skipped.is_empty() {
+        println!(
+            "  Skipped (too large):  {}",
+            summary.skipped.len().to_string().yellow()
+        );
+        for file in &summary.skipped {
+            println!("    - {} ({})", file.path.blue(), format_size(file.size));
+        }
+    }
+
+    if !summary.rate_limit_wait.is_zero() {
+        println!(
+            "  Rate-limit wait:      {}",
+            format!("{}s", summary.rate_limit_wait.as_secs()).yellow()
+        );
+    }
 }
 
-fn truncate_line(line: &str, max_len: usize) -> String {
-    let trimmed = line.trim();
-    if trimmed.len() > max_len {
-        format!("{}...", &trimmed[..max_len])
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 3] = ["KB", "MB", "GB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
+}
+
+/// Renders the line with the matched span highlighted, keeping at most
+/// `max_len` characters around the match.
+fn highlight_match(finding: &Finding, max_len: usize) -> String {
+    let chars: Vec<char> = finding.line_content.chars().collect();
+    let start = finding.start_column.saturating_sub(1).min(chars.len());
+    let end = if finding.end_line_number > finding.line_number {
+        chars.len()
     } else {
-        trimmed.to_string()
+        finding.end_column.min(chars.len()).max(start)
+    };
+
+    let mut from = chars[..start]
+        .iter()
+        .position(|c| !c.is_whitespace())
+        .unwrap_or(start);
+    let mut to = chars.len();
+    while to > end && chars[to - 1].is_whitespace() {
+        to -= 1;
+    }
+
+    if to - from > max_len {
+        let context = max_len.saturating_sub(end - start) / 2;
+        from = from.max(start.saturating_sub(context));
+        to = to.min(end + context).max(end);
     }
+
+    let text = |range: &[char]| range.iter().collect::<String>();
+    let prefix = if from > 0 && chars[..from].iter().any(|c| !c.is_whitespace()) { "..." } else { "" };
+    let suffix = if to < chars.len() && chars[to..].iter().any(|c| !c.is_whitespace()) { "..." } else { "" };
+
+    format!(
+        "{}{}{}{}{}",
+        prefix.dimmed(),
+        text(&chars[from..start]).dimmed(),
+        text(&chars[start..end]).red().bold(),
+        text(&chars[end..to]).dimmed(),
+        suffix.dimmed()
+    )
 }
@@ -1,25 +1,138 @@
-use crate::patterns::{get_default_patterns, SecretPattern};
+use crate::config::Config;
+use crate::filters::{FilterReason, PlaceholderFilter};
+use crate::patterns::{Confidence, SecretPattern, Severity};
+use crate::verify::Verified;
+use regex::Regex;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+static IGNORE_MARKER: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"mini-guardian:ignore(?:\[([^\]]*)\])?").unwrap());
+
+// Quoted strings, then unquoted values after `=` or `:`.
+static ENTROPY_CANDIDATE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#""([^"\s]+)"|'([^'\s]+)'|[:=]\s*([^\s'"`,;#]+)"#).unwrap()
+});
+
+pub const ENTROPY_BASE64_TYPE: &str = "High Entropy String (base64)";
+pub const ENTROPY_HEX_TYPE: &str = "High Entropy String (hex)";
+
+#[derive(Debug, Clone)]
+pub struct EntropyConfig {
+    pub base64_threshold: f64,
+    pub hex_threshold: f64,
+    pub min_length: usize,
+}
+
+impl Default for EntropyConfig {
+    fn default() -> Self {
+        Self {
+            base64_threshold: 4.5,
+            hex_threshold: 3.0,
+            min_length: 20,
+        }
+    }
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Finding {
     pub file_path: String,
     pub line_number: usize,
+    /// 1-based character column of the first matched character.
+    pub start_column: usize,
+    /// Last line of the match; equal to `line_number` unless the rule is multi-line.
+    pub end_line_number: usize,
+    /// 1-based character column of the last matched character, on `end_line_number`.
+    pub end_column: usize,
     pub line_content: String,
     pub secret_type: String,
     pub matched_text: String,
+    pub severity: Severity,
+    pub confidence: Confidence,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub branches: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit: Option<CommitInfo>,
+    /// Link to the line on GitHub, for remote scans.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub suppressed: Option<Suppression>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub validation: Option<Validation>,
+    /// Set by `--verify`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verified: Option<Verified>,
+}
+
+/// A file left out of the scan because it exceeds `--max-file-size`.
+#[derive(Debug, Clone, Serialize)]
+pub struct SkippedFile {
+    pub path: String,
+    pub size: u64,
+}
+
+/// Why a finding was matched but is not reported.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Suppression {
+    InlineIgnore,
+    /// Recognized by the false-positive filter as an example value.
+    Placeholder(FilterReason),
+}
+
+/// Outcome of the rule's offline validator, e.g. a token checksum.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Validation {
+    /// Passed: confidence raised to high.
+    Valid,
+    /// Failed: severity and confidence downgraded to low.
+    Invalid,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommitInfo {
+    pub sha: String,
+    pub author: String,
+    pub date: String,
 }
 
 impl Finding {
+    /// SHA-256 of the secret type and matched text, so a secret can be
+    /// identified across scans without storing it.
+    pub fn secret_hash(&self) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(self.secret_type.as_bytes());
+        hasher.update(b":");
+        hasher.update(self.matched_text.as_bytes());
+        format!("{:x}", hasher.finalize())
+    }
+
+    /// Stable identity of a finding for baselines: pattern, path and secret hash.
+    /// The line number is left out so unrelated edits do not resurface it.
+    pub fn fingerprint(&self) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(self.secret_type.as_bytes());
+        hasher.update(b"\0");
+        hasher.update(self.file_path.as_bytes());
+        hasher.update(b"\0");
+        hasher.update(self.secret_hash().as_bytes());
+        format!("{:x}", hasher.finalize())
+    }
+
     pub fn masked_text(&self) -> String {
-        let len = self.matched_text.len();
+        let chars: Vec<char> = self.matched_text.chars().collect();
+        let len = chars.len();
         if len <= 8 {
             "*".repeat(len)
         } else {
             format!(
                 "{}...{}",
-                &self.matched_text[..4],
-                &self.matched_text[len - 4..]
+                chars[..4].iter().collect::<String>(),
+                chars[len - 4..].iter().collect::<String>()
             )
         }
     }
@@ -27,42 +140,475 @@ impl Finding {
 
 pub struct Scanner {
     patterns: Vec<SecretPattern>,
+    entropy: Option<EntropyConfig>,
+    placeholders: Option<PlaceholderFilter>,
+    config: Config,
+    max_file_size: Option<u64>,
 }
 
 impl Scanner {
-    pub fn new() -> Self {
+    pub fn new(patterns: Vec<SecretPattern>) -> Self {
         Self {
-            patterns: get_default_patterns(),
+            patterns,
+            entropy: None,
+            placeholders: Some(PlaceholderFilter::default()),
+            config: Config::default(),
+            max_file_size: None,
         }
     }
 
+    /// Also reports quoted strings and assigned values whose Shannon entropy
+    /// exceeds the configured thresholds, to catch tokens without a known prefix.
+    pub fn with_entropy(mut self, config: EntropyConfig) -> Self {
+        self.entropy = Some(config);
+        self
+    }
+
+    /// Replaces the default false-positive filter; `None` reports every match.
+    pub fn with_placeholder_filter(mut self, filter: Option<PlaceholderFilter>) -> Self {
+        self.placeholders = filter;
+        self
+    }
+
+    /// Restricts scanned paths and the paths of each rule, from `--config`.
+    pub fn with_config(mut self, config: Config) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Files over `bytes` are skipped instead of fetched and scanned.
+    pub fn with_max_file_size(mut self, bytes: Option<u64>) -> Self {
+        self.max_file_size = bytes;
+        self
+    }
+
     pub fn patterns(&self) -> &[SecretPattern] {
         &self.patterns
     }
 
+    /// Whether the config's include/exclude globs let `path` be scanned at all.
+    pub fn in_scope(&self, path: &str) -> bool {
+        self.config.paths.allows(path)
+    }
+
+    pub fn too_large(&self, size: u64) -> bool {
+        self.max_file_size.is_some_and(|max| size > max)
+    }
+
+    fn rule_applies(&self, rule_name: &str, path: &str) -> bool {
+        self.config.rules.get(rule_name).is_none_or(|scope| scope.allows(path))
+    }
+
     pub fn scan_content(&self, file_path: &str, content: &str) -> Vec<Finding> {
+        let lines: Vec<(usize, &str)> = content
+            .lines()
+            .enumerate()
+            .map(|(i, line)| (i + 1, line))
+            .collect();
+        self.scan_lines(file_path, &lines)
+    }
+
+    pub fn scan_lines(&self, file_path: &str, lines: &[(usize, &str)]) -> Vec<Finding> {
         let mut findings = Vec::new();
+        let by_number: HashMap<usize, &str> = lines.iter().copied().collect();
+        let patterns: Vec<&SecretPattern> = self
+            .patterns
+            .iter()
+            .filter(|p| self.rule_applies(&p.name, file_path))
+            .collect();
 
-        for (line_number, line) in content.lines().enumerate() {
-            for pattern in &self.patterns {
-                if let Some(matched) = pattern.pattern.find(line) {
-                    findings.push(Finding {
-                        file_path: file_path.to_string(),
-                        line_number: line_number + 1,
-                        line_content: line.to_string(),
-                        secret_type: pattern.name.clone(),
-                        matched_text: matched.as_str().to_string(),
-                    });
+        for &(line_number, line) in lines {
+            let mut matched_spans = Vec::new();
+
+            let finding_at = |secret_type: &str, level: (Severity, Confidence), matched: regex::Match<'_>| {
+                let start_column = line[..matched.start()].chars().count() + 1;
+                let end_column = start_column + matched.as_str().chars().count() - 1;
+                Finding {
+                    severity: level.0,
+                    confidence: level.1,
+                    ..new_finding(
+                        file_path,
+                        &by_number,
+                        (line_number, line),
+                        secret_type,
+                        matched.as_str(),
+                        start_column,
+                        (line_number, end_column),
+                    )
+                }
+            };
+
+            for pattern in patterns.iter().filter(|p| !p.multiline) {
+                for matched in pattern.pattern.find_iter(line) {
+                    matched_spans.push(matched.range());
+                    let mut finding = finding_at(&pattern.name, (pattern.severity, pattern.confidence), matched);
+                    validate(pattern, &mut finding);
+                    findings.push(finding);
+                }
+            }
+
+            if let Some(config) = &self.entropy {
+                for candidate in entropy_candidates(line) {
+                    // A regex rule already reported this value.
+                    if matched_spans
+                        .iter()
+                        .any(|span| span.start < candidate.end() && candidate.start() < span.end)
+                    {
+                        continue;
+                    }
+                    if let Some(secret_type) = classify_entropy(candidate.as_str(), config)
+                        && self.rule_applies(secret_type, file_path)
+                    {
+                        findings.push(finding_at(secret_type, (Severity::Medium, Confidence::Low), candidate));
+                    }
+                }
+            }
+        }
+
+        // Multi-line rules never span a gap in line numbers (e.g. between diff hunks).
+        for block in lines.chunk_by(|a, b| b.0 == a.0 + 1) {
+            self.scan_multiline(&patterns, file_path, block, &by_number, &mut findings);
+        }
+
+        if let Some(filter) = &self.placeholders {
+            // A token whose checksum is valid is real whatever it spells.
+            for finding in findings
+                .iter_mut()
+                .filter(|f| f.suppressed.is_none() && f.validation != Some(Validation::Valid))
+            {
+                if let Some(reason) = filter.check(&finding.matched_text) {
+                    finding.suppressed = Some(Suppression::Placeholder(reason));
                 }
             }
         }
 
         findings
     }
+
+    /// Runs the multi-line rules over a block of consecutive lines joined with `\n`.
+    fn scan_multiline(
+        &self,
+        patterns: &[&SecretPattern],
+        file_path: &str,
+        block: &[(usize, &str)],
+        by_number: &HashMap<usize, &str>,
+        findings: &mut Vec<Finding>,
+    ) {
+        if !patterns.iter().any(|p| p.multiline) {
+            return;
+        }
+
+        let text = block
+            .iter()
+            .map(|(_, line)| *line)
+            .collect::<Vec<_>>()
+            .join("\n");
+        let mut line_starts = Vec::with_capacity(block.len());
+        let mut offset = 0;
+        for (_, line) in block {
+            line_starts.push(offset);
+            offset += line.len() + 1;
+        }
+
+        // Byte offset in `text` -> (index in `block`, 1-based character column).
+        let locate = |byte: usize| {
+            let index = line_starts.partition_point(|&start| start <= byte) - 1;
+            (index, text[line_starts[index]..byte].chars().count() + 1)
+        };
+
+        for pattern in patterns.iter().filter(|p| p.multiline) {
+            for matched in pattern.pattern.find_iter(&text) {
+                let last_char = matched
+                    .as_str()
+                    .char_indices()
+                    .last()
+                    .map_or(matched.start(), |(i, _)| matched.start() + i);
+                let (start_index, start_column) = locate(matched.start());
+                let (end_index, end_column) = locate(last_char);
+
+                let mut finding = Finding {
+                    severity: pattern.severity,
+                    confidence: pattern.confidence,
+                    ..new_finding(
+                        file_path,
+                        by_number,
+                        block[start_index],
+                        &pattern.name,
+                        matched.as_str(),
+                        start_column,
+                        (block[end_index].0, end_column),
+                    )
+                };
+                validate(pattern, &mut finding);
+                findings.push(finding);
+            }
+        }
+    }
 }
 
-impl Default for Scanner {
-    fn default() -> Self {
-        Self::new()
+/// Builds a finding starting on `line`, marking it suppressed when that line,
+/// or a comment-only line above it, carries an ignore marker for `secret_type`.
+/// Severity and confidence are left at their defaults for the caller to fill in.
+fn new_finding(
+    file_path: &str,
+    by_number: &HashMap<usize, &str>,
+    (line_number, line): (usize, &str),
+    secret_type: &str,
+    matched_text: &str,
+    start_column: usize,
+    (end_line_number, end_column): (usize, usize),
+) -> Finding {
+    let previous = line_number.checked_sub(1).and_then(|n| by_number.get(&n));
+    let ignored = is_ignored(line, secret_type)
+        || previous.is_some_and(|p| is_comment_only(p) && is_ignored(p, secret_type));
+
+    Finding {
+        file_path: file_path.to_string(),
+        line_number,
+        start_column,
+        end_line_number,
+        end_column,
+        line_content: line.to_string(),
+        secret_type: secret_type.to_string(),
+        matched_text: matched_text.to_string(),
+        severity: Severity::default(),
+        confidence: Confidence::default(),
+        branches: Vec::new(),
+        commit: None,
+        url: None,
+        suppressed: ignored.then_some(Suppression::InlineIgnore),
+        validation: None,
+        verified: None,
+    }
+}
+
+/// Runs the rule's validator, if any, and adjusts the finding's levels accordingly.
+fn validate(pattern: &SecretPattern, finding: &mut Finding) {
+    let Some(valid) = pattern.validator.and_then(|v| v.validate(&finding.matched_text)) else {
+        return;
+    };
+
+    if valid {
+        finding.validation = Some(Validation::Valid);
+        finding.confidence = Confidence::High;
+    } else {
+        finding.validation = Some(Validation::Invalid);
+        finding.severity = Severity::Low;
+        finding.confidence = Confidence::Low;
+    }
+}
+
+/// `mini-guardian:ignore` silences every rule, `mini-guardian:ignore[Rule A, Rule B]`
+/// only the listed ones.
+fn is_ignored(line: &str, rule_name: &str) -> bool {
+    IGNORE_MARKER.captures_iter(line).any(|caps| match caps.get(1) {
+        None => true,
+        Some(rules) => rules
+            .as_str()
+            .split(',')
+            .any(|r| r.trim().eq_ignore_ascii_case(rule_name)),
+    })
+}
+
+/// A line holding only a comment, so a marker on it is not a trailing comment
+/// about code on the same line.
+fn is_comment_only(line: &str) -> bool {
+    const COMMENT_PREFIXES: [&str; 8] = ["//", "#", "/*", "*", "--", ";", "<!--", "%"];
+    let line = line.trim_start();
+    COMMENT_PREFIXES.iter().any(|prefix| line.starts_with(prefix))
+}
+
+fn entropy_candidates(line: &str) -> impl Iterator<Item = regex::Match<'_>> {
+    ENTROPY_CANDIDATE
+        .captures_iter(line)
+        .filter_map(|caps| caps.get(1).or_else(|| caps.get(2)).or_else(|| caps.get(3)))
+}
+
+fn classify_entropy(value: &str, config: &EntropyConfig) -> Option<&'static str> {
+    if value.len() < config.min_length {
+        return None;
+    }
+
+    // Digits alone are ids or numbers rather than hex-encoded secrets.
+    let is_hex = value.chars().all(|c| c.is_ascii_hexdigit()) && value.chars().any(|c| c.is_ascii_alphabetic());
+    if is_hex {
+        return (shannon_entropy(value) > config.hex_threshold).then_some(ENTROPY_HEX_TYPE);
+    }
+
+    let is_base64 = value
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '/' | '=' | '-' | '_'));
+    if is_base64 {
+        return (shannon_entropy(value) > config.base64_threshold).then_some(ENTROPY_BASE64_TYPE);
+    }
+
+    None
+}
+
+fn shannon_entropy(value: &str) -> f64 {
+    let mut counts: HashMap<char, usize> = HashMap::new();
+    for c in value.chars() {
+        *counts.entry(c).or_default() += 1;
+    }
+
+    let len = value.chars().count() as f64;
+    counts
+        .values()
+        .map(|&count| {
+            let p = count as f64 / len;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::patterns::load_patterns;
+
+    // Split so that secret scanners do not flag the fixtures.
+    const AWS_KEY: &str = concat!("AKIA", "QRSTUVWXYZABCDEF");
+
+    fn scanner() -> Scanner {
+        Scanner::new(load_patterns(&[]).unwrap())
+    }
+
+    fn suppressed_lines(content: &str) -> Vec<(usize, bool)> {
+        scanner()
+            .scan_content("app.py", content)
+            .iter()
+            .map(|f| (f.line_number, f.suppressed == Some(Suppression::InlineIgnore)))
+            .collect()
+    }
+
+    #[test]
+    fn marker_on_a_comment_line_covers_the_next_line() {
+        let content = format!("# mini-guardian:ignore\nkey = \"{}\"\n", AWS_KEY);
+        assert_eq!(suppressed_lines(&content), [(2, true)]);
+
+        let content = format!("    // mini-guardian:ignore\n    key = \"{}\"\n", AWS_KEY);
+        assert_eq!(suppressed_lines(&content), [(2, true)]);
+    }
+
+    #[test]
+    fn trailing_marker_does_not_cover_the_next_line() {
+        let content = format!(
+            "a = \"{}\"  # mini-guardian:ignore\nb = \"{}\"\n",
+            AWS_KEY,
+            AWS_KEY.replace('Q', "R")
+        );
+        assert_eq!(suppressed_lines(&content), [(1, true), (2, false)]);
+    }
+
+    #[test]
+    fn marker_rule_list_is_case_insensitive() {
+        let content = format!("key = \"{}\"  # mini-guardian:ignore[Generic Password, aws ACCESS key id]\n", AWS_KEY);
+        assert_eq!(suppressed_lines(&content), [(1, true)]);
+
+        let content = format!("key = \"{}\"  # mini-guardian:ignore[Generic Password]\n", AWS_KEY);
+        assert_eq!(suppressed_lines(&content), [(1, false)]);
+    }
+
+    #[test]
+    fn columns_count_characters_after_a_multibyte_prefix() {
+        let other = AWS_KEY.replace('Q', "R");
+        let line = format!("é x = \"{}\" ü \"{}\"", AWS_KEY, other);
+        let findings = scanner().scan_content("app.py", &line);
+
+        let spans: Vec<(&str, usize, usize)> = findings
+            .iter()
+            .map(|f| (f.matched_text.as_str(), f.start_column, f.end_column))
+            .collect();
+        assert_eq!(spans, [(AWS_KEY, 8, 27), (other.as_str(), 33, 52)]);
+    }
+
+    #[test]
+    fn masked_text_handles_non_ascii_matches() {
+        let findings = scanner().scan_content("app.py", "password = \"héllo wörld ñññ\"");
+        assert_eq!(findings.len(), 1);
+        let masked = findings[0].masked_text();
+        assert!(masked.starts_with("pass"));
+        assert!(masked.ends_with("ñññ\""));
+
+        let short = Finding {
+            matched_text: "ééé".to_string(),
+            ..findings[0].clone()
+        };
+        assert_eq!(short.masked_text(), "***");
+    }
+
+    const PEM_BEGIN: &str = concat!("-----BEGIN RSA ", "PRIVATE KEY-----");
+    const PEM_END: &str = concat!("-----END RSA ", "PRIVATE KEY-----");
+    const PEM_BODY: &str = "MIIEowIBAAKCAQEAx4f1mDdPZ0tM3sZbq8N2yW7cH9qR6LkXvT5uJpO1nBwA3eYiGf";
+
+    fn private_keys(lines: &[(usize, &str)]) -> Vec<Finding> {
+        scanner()
+            .scan_lines("key.pem", lines)
+            .into_iter()
+            .filter(|f| f.secret_type == "Private Key")
+            .collect()
+    }
+
+    #[test]
+    fn pem_block_spans_its_lines() {
+        let begin = format!("  {}", PEM_BEGIN);
+        let end = format!("  {}", PEM_END);
+        let lines = [(1, "key: |"), (2, begin.as_str()), (3, PEM_BODY), (4, PEM_BODY), (5, end.as_str())];
+
+        let findings = private_keys(&lines);
+        assert_eq!(findings.len(), 1);
+        let finding = &findings[0];
+        assert_eq!((finding.line_number, finding.start_column), (2, 3));
+        assert_eq!((finding.end_line_number, finding.end_column), (5, 2 + PEM_END.len()));
+    }
+
+    #[test]
+    fn empty_pem_template_is_not_a_key() {
+        assert!(private_keys(&[(1, PEM_BEGIN), (2, PEM_END)]).is_empty());
+        assert!(private_keys(&[(1, PEM_BEGIN), (2, "..."), (3, PEM_END)]).is_empty());
+    }
+
+    #[test]
+    fn pem_in_a_string_with_escaped_newlines() {
+        let line = format!("KEY = \"{}\\n{}\\n{}\\n{}\"", PEM_BEGIN, PEM_BODY, PEM_BODY, PEM_END);
+        let findings = private_keys(&[(7, line.as_str())]);
+        assert_eq!(findings.len(), 1);
+        assert_eq!((findings[0].line_number, findings[0].end_line_number), (7, 7));
+        assert_eq!(findings[0].start_column, 8);
+    }
+
+    #[test]
+    fn pem_block_does_not_cross_a_gap_in_line_numbers() {
+        // Two hunks of a diff: the key is split across unrelated parts of the file.
+        let lines = [(1, PEM_BEGIN), (2, PEM_BODY), (40, PEM_BODY), (41, PEM_END)];
+        assert!(private_keys(&lines).is_empty());
+
+        let contiguous = [(1, PEM_BEGIN), (2, PEM_BODY), (3, PEM_BODY), (4, PEM_END)];
+        assert_eq!(private_keys(&contiguous).len(), 1);
+    }
+
+    #[test]
+    fn entropy_classifies_hex_only_with_a_letter() {
+        let config = EntropyConfig::default();
+        assert_eq!(classify_entropy("12345678901234567890123", &config), None);
+        assert_eq!(
+            classify_entropy("9f86d081884c7d659a2feaa0c55ad015a3bf4f1b", &config),
+            Some(ENTROPY_HEX_TYPE)
+        );
+        assert_eq!(classify_entropy("9f86d081", &config), None);
+    }
+
+    #[test]
+    fn entropy_skips_values_already_matched_by_a_rule() {
+        let token = concat!("ghp_", "R8hQx2mVt7LpWz4Kc9NbYf3JdA6sEu2qG38V");
+        assert_eq!(classify_entropy(token, &EntropyConfig::default()), Some(ENTROPY_BASE64_TYPE));
+
+        let scanner = scanner().with_entropy(EntropyConfig::default());
+        let types: Vec<String> = scanner
+            .scan_content("app.py", &format!("token = \"{}\"", token))
+            .into_iter()
+            .map(|f| f.secret_type)
+            .collect();
+        assert_eq!(types, ["GitHub Token"]);
     }
 }
@@ -0,0 +1,354 @@
+use clap::ValueEnum;
+use futures::future::join_all;
+use reqwest::{Client, RequestBuilder, StatusCode};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::sync::Semaphore;
+
+use crate::patterns::SecretPattern;
+use crate::scanner::Finding;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+const MAX_CONCURRENT_CHECKS: usize = 8;
+
+/// Whether a leaked secret is still accepted by its service.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Verified {
+    Active,
+    Inactive,
+    /// No verifier for this rule, or the service gave no clear answer.
+    Unknown,
+}
+
+impl Verified {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Verified::Active => "active",
+            Verified::Inactive => "inactive",
+            Verified::Unknown => "unknown",
+        }
+    }
+}
+
+/// Service a rule's matches can be checked against, selected per rule with
+/// `"verifier"` in the rule file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum Service {
+    Github,
+    Slack,
+    Stripe,
+}
+
+impl Service {
+    pub fn default_base_url(self) -> &'static str {
+        match self {
+            Service::Github => "https://api.github.com",
+            Service::Slack => "https://slack.com/api",
+            Service::Stripe => "https://api.stripe.com",
+        }
+    }
+
+    fn verifier(self, base_url: String) -> Box<dyn Verifier> {
+        match self {
+            Service::Github => Box::new(GitHubVerifier { base_url }),
+            Service::Slack => Box::new(SlackVerifier { base_url }),
+            Service::Stripe => Box::new(StripeVerifier { base_url }),
+        }
+    }
+}
+
+/// Checks a secret with a single read-only request to its service.
+pub trait Verifier: Send + Sync {
+    /// Builds the request that authenticates with `secret`.
+    fn request(&self, client: &Client, secret: &str) -> RequestBuilder;
+
+    /// Interprets the service's answer.
+    fn verdict(&self, status: StatusCode, body: &str) -> Verified;
+}
+
+/// `GET /user`: 200 for a live token, 401 for a revoked one.
+struct GitHubVerifier {
+    base_url: String,
+}
+
+impl Verifier for GitHubVerifier {
+    fn request(&self, client: &Client, secret: &str) -> RequestBuilder {
+        client
+            .get(format!("{}/user", self.base_url))
+            .bearer_auth(secret)
+            .header("Accept", "application/vnd.github+json")
+    }
+
+    fn verdict(&self, status: StatusCode, _body: &str) -> Verified {
+        status_verdict(status)
+    }
+}
+
+/// `POST auth.test`: always 200, the outcome is in the `ok` field.
+struct SlackVerifier {
+    base_url: String,
+}
+
+#[derive(Deserialize)]
+struct SlackAuthTest {
+    ok: bool,
+    #[serde(default)]
+    error: String,
+}
+
+impl Verifier for SlackVerifier {
+    fn request(&self, client: &Client, secret: &str) -> RequestBuilder {
+        client.post(format!("{}/auth.test", self.base_url)).bearer_auth(secret)
+    }
+
+    fn verdict(&self, status: StatusCode, body: &str) -> Verified {
+        if !status.is_success() {
+            return Verified::Unknown;
+        }
+        match serde_json::from_str::<SlackAuthTest>(body) {
+            Ok(answer) if answer.ok => Verified::Active,
+            Ok(answer) if matches!(answer.error.as_str(), "invalid_auth" | "token_revoked" | "account_inactive") => {
+                Verified::Inactive
+            }
+            _ => Verified::Unknown,
+        }
+    }
+}
+
+/// `GET /v1/balance`: 200 for a live key, 401 for a revoked one. A restricted
+/// key without balance access gets 403, but is still live.
+struct StripeVerifier {
+    base_url: String,
+}
+
+impl Verifier for StripeVerifier {
+    fn request(&self, client: &Client, secret: &str) -> RequestBuilder {
+        client.get(format!("{}/v1/balance", self.base_url)).bearer_auth(secret)
+    }
+
+    fn verdict(&self, status: StatusCode, _body: &str) -> Verified {
+        if status == StatusCode::FORBIDDEN {
+            return Verified::Active;
+        }
+        status_verdict(status)
+    }
+}
+
+fn status_verdict(status: StatusCode) -> Verified {
+    if status.is_success() {
+        Verified::Active
+    } else if status == StatusCode::UNAUTHORIZED {
+        Verified::Inactive
+    } else {
+        Verified::Unknown
+    }
+}
+
+#[derive(Debug)]
+pub enum VerifierError {
+    CaCert { path: PathBuf, message: String },
+    Client(reqwest::Error),
+}
+
+impl fmt::Display for VerifierError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VerifierError::CaCert { path, message } => {
+                write!(f, "invalid CA certificate {}: {}", path.display(), message)
+            }
+            VerifierError::Client(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for VerifierError {}
+
+/// Runs the verifier of each finding's rule, checking each distinct secret once.
+pub struct SecretVerifier {
+    client: Client,
+    verifiers: HashMap<Service, Box<dyn Verifier>>,
+}
+
+impl SecretVerifier {
+    /// `endpoints` overrides the base URL of some services, e.g. to point them at a mock server.
+    /// GitHub tokens are otherwise checked against `github_api_url`, the API the scan uses,
+    /// so GitHub Enterprise tokens never leave their server. `ca_cert` is a PEM file of
+    /// extra root certificates, as for the GitHub client.
+    pub fn new(
+        endpoints: &[(Service, String)],
+        github_api_url: &str,
+        ca_cert: Option<&Path>,
+    ) -> Result<Self, VerifierError> {
+        let mut builder = Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .user_agent(concat!("mini-guardian/", env!("CARGO_PKG_VERSION")));
+
+        if let Some(path) = ca_cert {
+            let invalid = |message: String| VerifierError::CaCert {
+                path: path.to_path_buf(),
+                message,
+            };
+            let pem = std::fs::read(path).map_err(|e| invalid(e.to_string()))?;
+            let certs = reqwest::Certificate::from_pem_bundle(&pem).map_err(|e| invalid(e.to_string()))?;
+            if certs.is_empty() {
+                return Err(invalid("no certificate found".to_string()));
+            }
+            for cert in certs {
+                builder = builder.add_root_certificate(cert);
+            }
+        }
+        let client = builder.build().map_err(VerifierError::Client)?;
+
+        let verifiers = Service::value_variants()
+            .iter()
+            .map(|&service| {
+                let default_url = match service {
+                    Service::Github => github_api_url,
+                    _ => service.default_base_url(),
+                };
+                let base_url = endpoints
+                    .iter()
+                    .rev()
+                    .find(|(s, _)| *s == service)
+                    .map_or(default_url, |(_, url)| url.as_str());
+                (service, service.verifier(base_url.trim_end_matches('/').to_string()))
+            })
+            .collect();
+
+        Ok(Self { client, verifiers })
+    }
+
+    /// Sets `verified` on every finding. Findings whose rule has no verifier are `unknown`.
+    pub async fn verify(&self, patterns: &[SecretPattern], findings: &mut [Finding]) {
+        let mut checks: Vec<(Service, String)> = Vec::new();
+        for finding in findings.iter() {
+            if let Some(service) = service_for(patterns, finding) {
+                let check = (service, finding.matched_text.clone());
+                if !checks.contains(&check) {
+                    checks.push(check);
+                }
+            }
+        }
+
+        let limiter = Semaphore::new(MAX_CONCURRENT_CHECKS);
+        let verdicts = join_all(checks.iter().map(|(service, secret)| {
+            let limiter = &limiter;
+            async move {
+                let _permit = limiter.acquire().await.expect("semaphore closed");
+                self.check(*service, secret).await
+            }
+        }))
+        .await;
+
+        let results: HashMap<&(Service, String), Verified> = checks.iter().zip(verdicts).collect();
+
+        for finding in findings.iter_mut() {
+            let verdict = service_for(patterns, finding)
+                .and_then(|service| results.get(&(service, finding.matched_text.clone())).copied());
+            finding.verified = Some(verdict.unwrap_or(Verified::Unknown));
+        }
+    }
+
+    async fn check(&self, service: Service, secret: &str) -> Verified {
+        let verifier = &self.verifiers[&service];
+        let response = match verifier.request(&self.client, secret).send().await {
+            Ok(r) => r,
+            Err(_) => return Verified::Unknown,
+        };
+
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        verifier.verdict(status, &body)
+    }
+}
+
+fn service_for(patterns: &[SecretPattern], finding: &Finding) -> Option<Service> {
+    patterns
+        .iter()
+        .find(|p| p.name == finding.secret_type)
+        .and_then(|p| p.verifier)
+}
+
+/// Parses `service=url` for `--verify-endpoint`.
+pub fn parse_endpoint(value: &str) -> Result<(Service, String), String> {
+    let (name, url) = value
+        .split_once('=')
+        .ok_or_else(|| format!("expected SERVICE=URL, got '{}'", value))?;
+    let service = Service::from_str(name, true)?;
+    if url.is_empty() {
+        return Err(format!("missing URL for {}", name));
+    }
+    Ok((service, url.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn verdict(service: Service, status: u16, body: &str) -> Verified {
+        let verifier = service.verifier("http://localhost".to_string());
+        verifier.verdict(StatusCode::from_u16(status).unwrap(), body)
+    }
+
+    #[test]
+    fn github_verdict_follows_status() {
+        assert_eq!(verdict(Service::Github, 200, "{}"), Verified::Active);
+        assert_eq!(verdict(Service::Github, 401, ""), Verified::Inactive);
+        assert_eq!(verdict(Service::Github, 403, ""), Verified::Unknown);
+        assert_eq!(verdict(Service::Github, 500, ""), Verified::Unknown);
+    }
+
+    #[test]
+    fn slack_verdict_reads_the_ok_field() {
+        assert_eq!(verdict(Service::Slack, 200, r#"{"ok":true}"#), Verified::Active);
+        assert_eq!(
+            verdict(Service::Slack, 200, r#"{"ok":false,"error":"invalid_auth"}"#),
+            Verified::Inactive
+        );
+        assert_eq!(
+            verdict(Service::Slack, 200, r#"{"ok":false,"error":"token_revoked"}"#),
+            Verified::Inactive
+        );
+        assert_eq!(
+            verdict(Service::Slack, 200, r#"{"ok":false,"error":"ratelimited"}"#),
+            Verified::Unknown
+        );
+        assert_eq!(verdict(Service::Slack, 200, "not json"), Verified::Unknown);
+        assert_eq!(verdict(Service::Slack, 503, r#"{"ok":true}"#), Verified::Unknown);
+    }
+
+    #[test]
+    fn stripe_restricted_key_is_active() {
+        assert_eq!(verdict(Service::Stripe, 200, "{}"), Verified::Active);
+        assert_eq!(verdict(Service::Stripe, 403, ""), Verified::Active);
+        assert_eq!(verdict(Service::Stripe, 401, ""), Verified::Inactive);
+        assert_eq!(verdict(Service::Stripe, 429, ""), Verified::Unknown);
+    }
+
+    #[test]
+    fn requests_use_the_configured_base_url() {
+        let client = Client::new();
+        let request = Service::Stripe
+            .verifier("http://localhost:8080".to_string())
+            .request(&client, "sk_test")
+            .build()
+            .unwrap();
+        assert_eq!(request.url().as_str(), "http://localhost:8080/v1/balance");
+    }
+
+    #[test]
+    fn parse_endpoint_splits_service_and_url() {
+        assert_eq!(
+            parse_endpoint("github=http://localhost:8080"),
+            Ok((Service::Github, "http://localhost:8080".to_string()))
+        );
+        assert!(parse_endpoint("github").is_err());
+        assert!(parse_endpoint("github=").is_err());
+        assert!(parse_endpoint("gitlab=http://localhost").is_err());
+    }
+}